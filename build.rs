@@ -0,0 +1,106 @@
+// Generate the static opcode tables used by lookup.rs from src/opcodes.txt. Each table has 512
+// entries, the base opcodes followed by the CB prefixed ones.
+
+use std::env;
+use std::fmt::Write;
+use std::fs;
+use std::path::Path;
+
+const TABLE: &str = "src/opcodes.txt";
+
+struct Row {
+    opcode: u16,
+    bytes: u8,
+    clocks: u8,
+    extra: u8,
+    flags: [char; 4],
+    name: String,
+}
+
+// Index into the tables for an opcode, CB prefixed opcodes come after the 256 base opcodes.
+fn index(opcode: u16) -> usize {
+    if opcode > 0xff { 0x100 + (opcode & 0xff) as usize } else { opcode as usize }
+}
+
+fn parse_row(line: &str) -> Result<Row, String> {
+    // The mnemonic is everything after the fifth column, and can contain spaces itself.
+    let words: Vec<&str> = line.split_whitespace().collect();
+    if words.len() < 6 {
+        return Err("expected 5 columns and a mnemonic".to_string());
+    }
+    let (cols, name) = (&words[..5], words[5..].join(" "));
+
+    let opcode = u16::from_str_radix(cols[0].trim_start_matches("0x"), 16)
+        .map_err(|e| format!("bad opcode \"{}\": {}", cols[0], e))?;
+    if opcode > 0xff && (opcode & 0xff00) != 0xcb00 {
+        return Err(format!("opcode 0x{:x} is out of range", opcode));
+    }
+
+    let num = |s: &str| s.parse::<u8>().map_err(|e| format!("bad number \"{}\": {}", s, e));
+    let flags: Vec<char> = cols[4].chars().collect();
+    if flags.len() != 4 {
+        return Err(format!("flags \"{}\" should have 4 characters", cols[4]));
+    }
+    for (c, letter) in flags.iter().zip("ZNHC".chars()) {
+        if !matches!(*c, '-' | '0' | '1') && *c != letter {
+            return Err(format!("flags \"{}\" should only contain -, 0, 1 or {}", cols[4], letter));
+        }
+    }
+
+    Ok(Row {
+        opcode,
+        bytes: num(cols[1])?,
+        clocks: num(cols[2])?,
+        extra: num(cols[3])?,
+        flags: [flags[0], flags[1], flags[2], flags[3]],
+        name,
+    })
+}
+
+fn flag_mod(c: char) -> &'static str {
+    match c {
+        '-' => "FlagMod::Ignore",
+        '0' => "FlagMod::Set(false)",
+        '1' => "FlagMod::Set(true)",
+        _   => "FlagMod::Eval",
+    }
+}
+
+fn main() {
+    println!("cargo:rerun-if-changed={}", TABLE);
+    println!("cargo:rerun-if-changed=build.rs");
+
+    let text = fs::read_to_string(TABLE).expect("Couldn't read the opcode table");
+    let mut rows: Vec<Option<Row>> = (0..512).map(|_| None).collect();
+    for (num, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') { continue; }
+
+        let row = parse_row(line).unwrap_or_else(|e| panic!("{}:{}: {}", TABLE, num + 1, e));
+        let slot = &mut rows[index(row.opcode)];
+        if slot.is_some() {
+            panic!("{}:{}: opcode 0x{:02x} is listed twice", TABLE, num + 1, row.opcode);
+        }
+        *slot = Some(row);
+    }
+
+    let mut insts = String::from("pub static INSTRUCTIONS: [Instruction; 512] = [\n");
+    let mut flagmods = String::from("pub static FLAGMODS: [FlagStatus; 512] = [\n");
+    for (i, row) in rows.iter().enumerate() {
+        let row = row.as_ref().unwrap_or_else(|| {
+            let opcode = if i > 0xff { 0xcb00 | (i & 0xff) } else { i };
+            panic!("{}: opcode 0x{:02x} is missing", TABLE, opcode)
+        });
+        writeln!(insts, "    Instruction {{ opcode: 0x{:02x}, prefix_cb: {}, name: {:?}, bytes: {}, clocks: {}, \
+                         clocks_extra: {}, modifies_flags: {} }},",
+                 row.opcode & 0xff, row.opcode > 0xff, row.name, row.bytes, row.clocks, row.extra,
+                 row.flags.iter().any(|c| *c != '-')).unwrap();
+        writeln!(flagmods, "    FlagStatus {{ z: {}, n: {}, h: {}, cy: {} }},", flag_mod(row.flags[0]),
+                 flag_mod(row.flags[1]), flag_mod(row.flags[2]), flag_mod(row.flags[3])).unwrap();
+    }
+    insts.push_str("];\n\n");
+    flagmods.push_str("];\n");
+
+    let out = Path::new(&env::var("OUT_DIR").unwrap()).join("opcodes.rs");
+    fs::write(out, insts + &flagmods).expect("Couldn't write the generated opcode tables");
+}
@@ -0,0 +1,138 @@
+// Assembly of single instructions, for patching code from the debugger. Instructions are matched
+// against the mnemonics in the opcode table, so anything the disassembler prints can be assembled
+// back. Both RGBDS syntax ("ld a, [hl+]", "ldh [c], a") and the table's own ("LD A,(HL+)") work.
+
+use crate::lookup;
+
+// Parse a number in RGBDS syntax ($ for hex, % for binary, otherwise decimal), or with a 0x prefix.
+fn parse_num(s: &str) -> Option<i32> {
+    let (negative, s) = match s.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, s.strip_prefix('+').unwrap_or(s)),
+    };
+    let val = if let Some(hex) = s.strip_prefix('$').or_else(|| s.strip_prefix("0x")) {
+        i32::from_str_radix(hex, 16).ok()?
+    } else if let Some(bin) = s.strip_prefix('%') {
+        i32::from_str_radix(bin, 2).ok()?
+    } else {
+        s.parse().ok()?
+    };
+    Some(if negative { -val } else { val })
+}
+
+// Put an operand into the same form as the opcode table, lowercased without spaces and with
+// parentheses for memory operands.
+fn normalize_operand(op: &str) -> String {
+    let op: String = op.chars().filter(|c| !c.is_whitespace()).collect::<String>().to_lowercase();
+    let op = op.replace('[', "(").replace(']', ")");
+    match op.as_str() {
+        "(hli)" => "(hl+)".to_string(),
+        "(hld)" => "(hl-)".to_string(),
+        "($ff00+c)" | "(0xff00+c)" => "(c)".to_string(),
+        _ => op,
+    }
+}
+
+// Match one operand against its pattern from the opcode table, returning the bytes it encodes to.
+// next_pc is the address after the instruction, for relative jumps.
+fn match_operand(pattern: &str, op: &str, mnemonic: &str, next_pc: u16) -> Result<Option<Vec<u8>>, String> {
+    let memory = || op.strip_prefix('(').and_then(|o| o.strip_suffix(')')).and_then(parse_num);
+    let bytes = match pattern {
+        "d8" => parse_num(op).filter(|v| (-128..=0xFF).contains(v)).map(|v| vec![v as u8]),
+        "d16" | "a16" => parse_num(op).filter(|v| (0..=0xFFFF).contains(v)).map(|v| (v as u16).to_le_bytes().to_vec()),
+        "(a16)" => memory().filter(|v| (0..=0xFFFF).contains(v)).map(|v| (v as u16).to_le_bytes().to_vec()),
+        "(a8)" => memory().filter(|v| (0..=0xFF).contains(v) || (0xFF00..=0xFFFF).contains(v)).map(|v| vec![v as u8]),
+        "r8" if mnemonic == "jr" => match parse_num(op) {
+            Some(target) => {
+                let offset = target - next_pc as i32;
+                if !(-128..=127).contains(&offset) {
+                    return Err(format!("jr target ${:04x} is out of range", target));
+                }
+                Some(vec![offset as u8])
+            },
+            None => None,
+        },
+        "r8" => parse_num(op).filter(|v| (-128..=127).contains(v)).map(|v| vec![v as u8]),
+        "sp+r8" => op.strip_prefix("sp").and_then(parse_num).filter(|v| (-128..=127).contains(v)).map(|v| vec![v as u8]),
+        _ if mnemonic == "rst" => {
+            let vector = i32::from_str_radix(pattern.trim_end_matches('h'), 16).ok();
+            if parse_num(op) == vector { Some(vec![]) } else { None }
+        },
+        _ if pattern == op => Some(vec![]),
+        _ => None,
+    };
+    Ok(bytes)
+}
+
+// Assemble one instruction to be placed at addr.
+pub fn assemble(text: &str, addr: u16) -> Result<Vec<u8>, String> {
+    let text = text.trim();
+    let (mnemonic, operands) = text.split_once(char::is_whitespace).unwrap_or((text, ""));
+    let mut mnemonic = mnemonic.to_lowercase();
+    let mut operands: Vec<String> = operands.split(',').map(normalize_operand).filter(|op| !op.is_empty()).collect();
+
+    // RGBDS writes LD (C),A as LDH, allows the A to be left out of 8-bit ALU instructions that
+    // the opcode table writes with it, and writes STOP without its padding byte.
+    if mnemonic == "ldh" && operands.iter().any(|op| op == "(c)") { mnemonic = "ld".to_string(); }
+    if matches!(mnemonic.as_str(), "add" | "adc" | "sbc") && operands.len() == 1 { operands.insert(0, "a".to_string()); }
+    if matches!(mnemonic.as_str(), "sub" | "and" | "xor" | "or" | "cp") && operands.len() == 2 && operands[0] == "a" {
+        operands.remove(0);
+    }
+    if mnemonic == "stop" && operands.is_empty() { operands.push("0".to_string()); }
+
+    let mut error = None;
+    for opcode in (0x00..=0xFF).chain(0xCB00..=0xCBFF) {
+        let inst = lookup::get_instruction(opcode);
+        let (inst_mnemonic, patterns) = inst.name.split_once(' ').unwrap_or((inst.name, ""));
+        let patterns: Vec<String> = patterns.split(',').filter(|p| !p.is_empty()).map(str::to_lowercase).collect();
+        if opcode == 0xCB || inst_mnemonic.to_lowercase() != mnemonic || patterns.len() != operands.len() {
+            continue;
+        }
+
+        let mut bytes = if opcode > 0xFF { vec![0xCB, opcode as u8] } else { vec![opcode as u8] };
+        let next_pc = addr.wrapping_add(inst.bytes as u16);
+        let mut matched = true;
+        for (pattern, op) in patterns.iter().zip(operands.iter()) {
+            match match_operand(pattern, op, &mnemonic, next_pc) {
+                Ok(Some(operand_bytes)) => bytes.extend(operand_bytes),
+                Ok(None) => { matched = false; break; },
+                Err(e) => { error = Some(e); matched = false; break; },
+            }
+        }
+        if matched {
+            bytes.resize(inst.bytes as usize, 0x00);
+            return Ok(bytes);
+        }
+    }
+
+    Err(error.unwrap_or_else(|| format!("no instruction matches \"{}\"", text)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::disasm::disassemble;
+
+    #[test]
+    fn assembles_everything_the_disassembler_prints() {
+        for opcode in (0x00..=0xFFu16).chain(0xCB00..=0xCBFF) {
+            let inst = lookup::get_instruction(opcode);
+            if opcode == 0xCB || inst.name.starts_with("UNKNOWN") { continue; }
+
+            let mut bytes = if opcode > 0xFF { vec![0xCB, opcode as u8] } else { vec![opcode as u8] };
+            bytes.resize(inst.bytes as usize, 0xF6);
+            if opcode == 0x10 { bytes[1] = 0x00; }
+            let (text, _) = disassemble(&bytes, 0x0150);
+            assert_eq!(assemble(&text, 0x0150), Ok(bytes), "{}", text);
+        }
+    }
+
+    #[test]
+    fn accepts_the_opcode_table_syntax_and_reports_errors() {
+        assert_eq!(assemble("LD A,(HL+)", 0), Ok(vec![0x2A]));
+        assert_eq!(assemble("ld [$ff00+c], a", 0), Ok(vec![0xE2]));
+        assert_eq!(assemble("cp a, 10", 0), Ok(vec![0xFE, 0x0A]));
+        assert_eq!(assemble("jr $0100", 0x0200), Err("jr target $0100 is out of range".to_string()));
+        assert_eq!(assemble("ld a, [bc+]", 0), Err("no instruction matches \"ld a, [bc+]\"".to_string()));
+    }
+}
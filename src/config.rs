@@ -0,0 +1,197 @@
+// The user config file, which sets up the keyboard and controller bindings. It's read once at startup,
+// from --config or otherwise gblite/config in the user's config directory, if it's there:
+//
+//   # Lines starting with # are comments.
+//   [bindings]
+//   A     = key:X, pad:A
+//   Start = key:Return, key:Space, pad:Start
+//   quit  = key:Escape
+//
+// Each line binds a Game Boy button (Right, Left, Up, Down, A, B, Select or Start) or an emulator
+// hotkey (quit, or debug to break into the debugger) to keys and controller buttons, by their SDL
+// names. Anything bound in the file replaces the defaults for it, and the rest keep their defaults.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use sdl2::controller::Button as PadButton;
+use sdl2::keyboard::Keycode;
+
+use crate::joypad::Button;
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Hotkey {
+    Quit,
+    Debug, // Break into the debugger before the next instruction.
+}
+
+// What an input does when it's pressed.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Action {
+    Button(Button),
+    Hotkey(Hotkey),
+}
+
+// A key or controller button that can be bound.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Binding {
+    Key(Keycode),
+    Pad(PadButton),
+}
+
+const ACTIONS: [(&str, Action); 10] = [
+    ("right", Action::Button(Button::Right)), ("left", Action::Button(Button::Left)),
+    ("up", Action::Button(Button::Up)), ("down", Action::Button(Button::Down)),
+    ("a", Action::Button(Button::A)), ("b", Action::Button(Button::B)),
+    ("select", Action::Button(Button::Select)), ("start", Action::Button(Button::Start)),
+    ("quit", Action::Hotkey(Hotkey::Quit)), ("debug", Action::Hotkey(Hotkey::Debug)),
+];
+
+// The keyboard controls are the arrow keys for the d-pad, X and Z for A and B, and Enter and
+// Backspace for Start and Select. Controllers use their d-pad, the bottom and right face buttons
+// (and the shoulder buttons) for A and B, and Back and Start for Select and Start.
+const DEFAULT_BINDINGS: [(Binding, Action); 20] = [
+    (Binding::Key(Keycode::Right), Action::Button(Button::Right)),
+    (Binding::Key(Keycode::Left), Action::Button(Button::Left)),
+    (Binding::Key(Keycode::Up), Action::Button(Button::Up)),
+    (Binding::Key(Keycode::Down), Action::Button(Button::Down)),
+    (Binding::Key(Keycode::X), Action::Button(Button::A)),
+    (Binding::Key(Keycode::Z), Action::Button(Button::B)),
+    (Binding::Key(Keycode::Backspace), Action::Button(Button::Select)),
+    (Binding::Key(Keycode::Return), Action::Button(Button::Start)),
+    (Binding::Key(Keycode::Escape), Action::Hotkey(Hotkey::Quit)),
+    (Binding::Key(Keycode::F12), Action::Hotkey(Hotkey::Debug)),
+    (Binding::Pad(PadButton::DPadRight), Action::Button(Button::Right)),
+    (Binding::Pad(PadButton::DPadLeft), Action::Button(Button::Left)),
+    (Binding::Pad(PadButton::DPadUp), Action::Button(Button::Up)),
+    (Binding::Pad(PadButton::DPadDown), Action::Button(Button::Down)),
+    (Binding::Pad(PadButton::A), Action::Button(Button::A)),
+    (Binding::Pad(PadButton::B), Action::Button(Button::B)),
+    (Binding::Pad(PadButton::LeftShoulder), Action::Button(Button::B)),
+    (Binding::Pad(PadButton::RightShoulder), Action::Button(Button::A)),
+    (Binding::Pad(PadButton::Back), Action::Button(Button::Select)),
+    (Binding::Pad(PadButton::Start), Action::Button(Button::Start)),
+];
+
+#[derive(Clone)]
+pub struct Bindings {
+    bindings: Vec<(Binding, Action)>,
+}
+
+impl Bindings {
+    pub fn defaults() -> Bindings {
+        Bindings { bindings: DEFAULT_BINDINGS.to_vec() }
+    }
+
+    // The actions bound to an input.
+    pub fn actions(&self, input: Binding) -> impl Iterator<Item = Action> + '_ {
+        self.bindings.iter().filter(move |(b, _)| *b == input).map(|(_, action)| *action)
+    }
+
+    // Parse a config file's contents. Every problem is reported, each with the line it's on.
+    pub fn parse(text: &str, file: &str) -> Result<Bindings, Vec<String>> {
+        let mut configured: Vec<(Binding, Action)> = Vec::new();
+        let mut errors = Vec::new();
+        let mut section = None;
+
+        for (i, line) in text.lines().enumerate() {
+            let mut error = |msg: String| errors.push(format!("{}:{}: {}", file, i + 1, msg));
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') { continue; }
+
+            if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+                if name.trim() != "bindings" { error(format!("unknown section [{}], the only section is [bindings]", name)); }
+                section = Some(name.trim().to_string());
+                continue;
+            }
+            match section.as_deref() {
+                Some("bindings") => (),
+                Some(_) => continue,
+                None => { error("bindings need to be in the [bindings] section".to_string()); continue; },
+            }
+
+            let (name, inputs) = match line.split_once('=') {
+                Some((name, inputs)) => (name.trim(), inputs.trim()),
+                None => { error(format!("expected \"button = inputs\", not \"{}\"", line)); continue; },
+            };
+            let action = match ACTIONS.iter().find(|(n, _)| n.eq_ignore_ascii_case(name)) {
+                Some((_, action)) => *action,
+                None => {
+                    let names: Vec<&str> = ACTIONS.iter().map(|(n, _)| *n).collect();
+                    error(format!("\"{}\" isn't a button or hotkey, expected one of {}", name, names.join(", ")));
+                    continue;
+                },
+            };
+
+            for input in inputs.split(',').map(str::trim) {
+                let binding = match parse_binding(input) {
+                    Ok(binding) => binding,
+                    Err(e) => { error(e); continue; },
+                };
+                match configured.iter().find(|(b, _)| *b == binding) {
+                    Some((_, other)) if *other != action => {
+                        error(format!("{} is already bound to {}", input, action_name(*other)));
+                    },
+                    Some(_) => (),
+                    None => configured.push((binding, action)),
+                }
+            }
+        }
+
+        if !errors.is_empty() { return Err(errors); }
+
+        // Keep the defaults for anything the file doesn't mention, unless their input was taken.
+        let mut bindings: Vec<(Binding, Action)> = DEFAULT_BINDINGS.iter().copied().filter(|(binding, action)| {
+            !configured.iter().any(|(b, a)| a == action || b == binding)
+        }).collect();
+        bindings.extend(configured);
+        Ok(Bindings { bindings })
+    }
+
+    // Load the bindings from a config file.
+    pub fn load(path: &Path) -> Result<Bindings, Vec<String>> {
+        let text = fs::read_to_string(path).map_err(|e| vec![format!("{}: {}", path.display(), e)])?;
+        Bindings::parse(&text, &path.display().to_string())
+    }
+}
+
+fn action_name(action: Action) -> &'static str {
+    ACTIONS.iter().find(|(_, a)| *a == action).map(|(name, _)| *name).unwrap_or("?")
+}
+
+fn parse_binding(input: &str) -> Result<Binding, String> {
+    match input.split_once(':') {
+        Some(("key", name)) => Keycode::from_name(name).map(Binding::Key)
+            .ok_or_else(|| format!("unknown key \"{}\", keys use SDL's names like Return, Left or F1", name)),
+        Some(("pad", name)) => PadButton::from_string(name).map(Binding::Pad)
+            .ok_or_else(|| format!("unknown controller button \"{}\", buttons use SDL's names like a, start or dpup", name)),
+        _ => Err(format!("\"{}\" isn't an input, expected key:<name> or pad:<name>", input)),
+    }
+}
+
+// Where the config file is looked for when --config isn't given.
+pub fn default_path() -> Option<PathBuf> {
+    let dir = std::env::var_os("XDG_CONFIG_HOME").map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+    Some(dir.join("gblite").join("config"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reports_every_problem_with_its_line() {
+        let text = "up = key:Up\n[bindings]\n# A comment\nturbo = key:T\nstart = joy:1\nselect\n[audio]\n";
+        assert_eq!(Bindings::parse(text, "config").err(), Some(vec![
+            "config:1: bindings need to be in the [bindings] section".to_string(),
+            "config:4: \"turbo\" isn't a button or hotkey, expected one of right, left, up, down, a, b, select, start, quit, debug".to_string(),
+            "config:5: \"joy:1\" isn't an input, expected key:<name> or pad:<name>".to_string(),
+            "config:6: expected \"button = inputs\", not \"select\"".to_string(),
+            "config:7: unknown section [audio], the only section is [bindings]".to_string(),
+        ]));
+
+        let defaults = Bindings::parse("[bindings]\n", "config").ok().unwrap();
+        assert_eq!(defaults.actions(Binding::Key(Keycode::Escape)).collect::<Vec<_>>(), vec![Action::Hotkey(Hotkey::Quit)]);
+    }
+}
@@ -10,13 +10,22 @@ use chrono::{Utc, Datelike, Timelike};
 
 use crate::memory::Memory;
 use crate::memory::MemClient;
+use crate::memory::{P1_ADDR, KEY1_ADDR, BOOT_ADDR};
+use crate::model::Model;
+use crate::timer::DIV_ADDR;
 use crate::ppu::{PPU, PPUReg};
+use crate::interrupt::{self, Interrupt};
 use crate::lookup::Instruction;
 use crate::registers::*;
 use crate::util;
+use crate::disasm;
+use crate::asm;
+use crate::trace::{TraceLog, TraceState};
 use crate::lookup;
 use crate::RuntimeConfig;
 
+use blocks::{BlockCache, Decoded};
+
 #[derive(Copy, Clone, PartialEq)]
 enum AluOp {
     Add(bool),
@@ -56,13 +65,53 @@ impl fmt::Display for AluOp {
     }
 }
 
+// Why execution stopped and handed control to the debugger.
+#[derive(Copy, Clone, PartialEq)]
+enum BreakReason {
+    Breakpoint,
+    Step,
+    Lockup(u8),
+    Instruction(u64)
+}
+
+impl fmt::Display for BreakReason {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            BreakReason::Breakpoint => write!(f, "Hit breakpoint"),
+            BreakReason::Step => write!(f, "Stepped"),
+            BreakReason::Lockup(op) => write!(f, "CPU locked up on illegal opcode 0x{:02x}", op),
+            BreakReason::Instruction(n) => write!(f, "Reached instruction {} (line {} of a trace)", n, n + 1),
+        }
+    }
+}
+
+// The interrupt master enable (IME) flag. EI doesn't enable interrupts until the instruction after
+// it has executed, so IME passes through the Pending state first. DI and RETI take effect at once.
+#[derive(Copy, Clone, PartialEq)]
+enum ImeState {
+    Disabled,
+    Pending,
+    Enabled
+}
+
 pub struct CPU {
     pub regs: RegisterCache,
     pub mem: Arc<Mutex<Memory>>,
     pub ppu: PPU,
-    inst: Instruction,
-    flagmod: FlagStatus,
-    ir_enabled: bool,
+    inst: &'static Instruction,
+    flagmod: &'static FlagStatus,
+    ime: ImeState,
+    halted: bool,
+    halt_bug: bool,
+    stopped: bool,
+    locked: bool,
+    cgb: bool,
+    double_speed: bool,
+    slow_cycle: bool,
+    branch_taken: bool,
+    inst_pc: u16,
+    mcycles: u32,
+    clocks: u64,
     quit: bool,
     flag_z: bool,
     flag_n: bool,
@@ -74,17 +123,40 @@ pub struct CPU {
     stepover_break: Option<u16>,
     last_break_arg: Option<String>,
     verbose: bool,
+    headless: bool,
+    magic_breaks: bool,
+    hit_magic_break: bool,
+    blocks: Option<BlockCache>,
+    operands: [u8; 2],
+    operands_left: u8,
+    trace: Option<TraceLog>,
+    instructions: u64,
+    break_at_instruction: Option<u64>,
 }
 
 impl CPU {
     pub fn new(mem: Arc<Mutex<Memory>>, ppu: PPU, rcfg: &RuntimeConfig) -> CPU {
+        // CGB features like the double speed mode need both a CGB model and a game that supports them.
+        let cgb_rom = mem.lock().unwrap().is_cgb_rom();
+        let model = rcfg.model.unwrap_or_else(|| Model::for_rom(mem.lock().unwrap().rom()));
         let mut c = CPU {
             regs: RegisterCache::new(),
             mem: mem,
             ppu: ppu,
             inst: lookup::get_instruction(0x0),
             flagmod: lookup::get_flagmod(0x0),
-            ir_enabled: true,
+            ime: ImeState::Disabled,
+            halted: false,
+            halt_bug: false,
+            stopped: false,
+            locked: false,
+            cgb: model.is_cgb() && cgb_rom,
+            double_speed: false,
+            slow_cycle: false,
+            branch_taken: false,
+            inst_pc: 0,
+            mcycles: 0,
+            clocks: 0,
             quit: false,
             flag_z: true,
             flag_n: false,
@@ -96,23 +168,45 @@ impl CPU {
             stepover_break: None,
             last_break_arg: None,
             verbose: rcfg.verbose,
+            headless: rcfg.headless,
+            magic_breaks: rcfg.mooneye_test,
+            hit_magic_break: false,
+            blocks: if rcfg.block_cache { Some(BlockCache::new()) } else { None },
+            operands: [0; 2],
+            operands_left: 0,
+            trace: None,
+            instructions: 0,
+            break_at_instruction: rcfg.break_at_instruction,
         };
 
-        // Setup initial register values
-        // TODO: modify this for GBC
-        c.regs.set(Reg8::A, 0x01);
-        c.regs.set(Reg8::C, 0x13);
-        c.regs.set(Reg16::HL, 0x014D);
-        c.regs.set(Reg16::SP, 0xFFFE);
-        c.regs.set_flag(Flag::Z, c.flag_z);
-        c.regs.set_flag(Flag::N, c.flag_n);
-        c.regs.set_flag(Flag::H, c.flag_h);
-        c.regs.set_flag(Flag::CY, c.flag_cy);
-
+        c.power_on(model);
         c
     }
 
-    // Lock the memory object and return byte at the given memory address.
+    // Put the CPU and I/O registers into the state the model's boot ROM leaves them in, and start
+    // at the cartridge entry point with the boot ROM unmapped.
+    fn power_on(&mut self, model: Model) {
+        let state = model.power_on_state(self.mem.lock().unwrap().rom());
+        self.regs.set(Reg16::AF, state.af);
+        self.regs.set(Reg16::BC, state.bc);
+        self.regs.set(Reg16::DE, state.de);
+        self.regs.set(Reg16::HL, state.hl);
+        self.regs.set(Reg16::SP, 0xFFFE);
+        self.regs.set(Reg16::PC, 0x0100);
+        self.load_flags();
+
+        for (addr, val) in state.io {
+            if addr == DIV_ADDR {
+                self.mem.lock().unwrap().set_divider(val);
+            } else {
+                self.mem_set(val, addr);
+            }
+        }
+        self.mem_set(0x01, BOOT_ADDR);
+    }
+
+    // Lock the memory object and return byte at the given memory address. This peeks at memory
+    // without taking any time, instructions should use read_cycle() instead.
     fn mem_get(&self, addr: u16) -> u8 {
         let mref = self.mem.lock().unwrap();
         (*mref).get(addr, MemClient::CPU)
@@ -124,39 +218,87 @@ impl CPU {
         (*mref).set(val, addr, MemClient::CPU);
     }
 
+    // Spend one machine cycle without accessing memory, letting the rest of the system run.
+    fn cycle(&mut self) {
+        self.mcycles += 1;
+        self.tick_peripherals();
+    }
+
+    // Read a byte from memory on its own machine cycle. The peripherals are run up to the point of
+    // the access first, so reads of registers like LY and STAT see the state for this exact cycle.
+    fn read_cycle(&mut self, addr: u16) -> u8 {
+        self.cycle();
+        let mut mref = self.mem.lock().unwrap();
+        (*mref).read_bus(addr)
+    }
+
+    // Write a byte to memory on its own machine cycle.
+    fn write_cycle(&mut self, val: u8, addr: u16) {
+        self.cycle();
+        {
+            let mut mref = self.mem.lock().unwrap();
+            (*mref).write_bus(val, addr);
+        }
+        let clocks = self.clocks + self.system_clocks(self.mcycles);
+        if let Some(blocks) = &mut self.blocks {
+            blocks.note_write(addr, val, clocks);
+        }
+    }
+
+    // Read the byte at PC and increment PC. Instructions from the block cache come with their
+    // operands, which take the same cycle without reading memory.
+    fn fetch8(&mut self) -> u8 {
+        let pc = self.regs.get(Reg16::PC);
+        self.regs.set(Reg16::PC, pc.wrapping_add(1));
+        if self.operands_left > 0 {
+            let val = self.operands[0];
+            self.operands = [self.operands[1], 0];
+            self.operands_left -= 1;
+            self.cycle();
+            return val;
+        }
+        self.read_cycle(pc)
+    }
+
+    // Read the little endian u16 at PC, and increment PC past it.
+    fn fetch16(&mut self) -> u16 {
+        let lo = self.fetch8();
+        let hi = self.fetch8();
+        util::join_u8((lo, hi))
+    }
+
     // Get the u16 value starting at $(addr), little endian.
     // TODO: move this to a memory controller class. We should be able to create a memory
     // client object that manages accesses to memory and has utility functions like this.
     fn parse_u16(&self, addr: u16) -> u16 {
-        util::join_u8((self.mem_get(addr), self.mem_get(addr+1)))
+        util::join_u8((self.mem_get(addr), self.mem_get(addr.wrapping_add(1))))
     }
 
-    // Push addr from given register onto stack
+    // Push addr from given register onto stack. SP is decremented on an internal cycle first.
     fn push(&mut self, src: Reg16) {
-        self.regs.sub(Reg16::SP, 2);
-        let sp_val = self.regs.get(Reg16::SP);
-        let split_addr = util::split_u16(self.regs.get(src));
-        self.mem_set(split_addr.0, sp_val);
-        self.mem_set(split_addr.1, sp_val+1);
+        self.cycle();
+        let val = self.regs.get(src);
+        self.push_u16(val);
     }
 
-    // Pop topmost u16 value from stack, store to given register
-    fn pop(&mut self, dst: Reg16) {
-        let stack_val = self.parse_u16(self.regs.get(Reg16::SP));
-        self.regs.add(Reg16::SP, 2);
-        self.regs.set(dst, stack_val);
+    // Write a u16 value to the stack, high byte first.
+    fn push_u16(&mut self, val: u16) {
+        let (lo, hi) = util::split_u16(val);
+        self.regs.sub(Reg16::SP, 1);
+        self.write_cycle(hi, self.regs.get(Reg16::SP));
+        self.regs.sub(Reg16::SP, 1);
+        self.write_cycle(lo, self.regs.get(Reg16::SP));
     }
 
-    // Call the value at address if flag value is set, or unset.
-    fn call_flag(&mut self, flag: Flag, if_unset: bool, addr: u16) {
-        let flag_val = match flag {
-            Flag::Z  => self.flag_z,
-            Flag::CY => self.flag_cy,
-            _ => panic!("CALL for flag only exists for Z and CY flags!")
-        };
-
-        if flag_val ^ if_unset {
-            self.call(addr);
+    // Pop topmost u16 value from stack, store to given register. Popping AF loads the flags too.
+    fn pop(&mut self, dst: Reg16) {
+        let lo = self.read_cycle(self.regs.get(Reg16::SP));
+        self.regs.add(Reg16::SP, 1);
+        let hi = self.read_cycle(self.regs.get(Reg16::SP));
+        self.regs.add(Reg16::SP, 1);
+        self.regs.set(dst, util::join_u8((lo, hi)));
+        if let Reg16::AF = dst {
+            self.load_flags();
         }
     }
 
@@ -166,52 +308,34 @@ impl CPU {
         self.regs.set(Reg16::PC, jump_addr);
     }
 
-    // Execute a return if given flag is set, or unset.
-    fn ret_flag(&mut self, flag: Flag, if_unset: bool) {
-        let flag_val = match flag {
-            Flag::Z  => self.flag_z,
-            Flag::CY => self.flag_cy,
-            _ => panic!("RET for flag only exists for Z and CY flags!")
-        };
-
-        if flag_val ^ if_unset {
-            self.ret(false);
-        }
-    }
-
-    // Pop the topmost address from the stack, and jump to it.
+    // Pop the topmost address from the stack, and jump to it. RETI enables IME immediately, unlike EI.
     fn ret(&mut self, enable_ir: bool) {
         self.pop(Reg16::PC);
+        self.cycle();
         if enable_ir {
-            self.ir_enabled = true;
+            self.ime = ImeState::Enabled;
         }
     }
 
-    // Copy from given register into the memory address pointed to by given Reg16
-    fn set_reg_ptr(&mut self, dst: Reg16, src: Reg8) {
-        let addr = self.regs.get(src);
-        let val = self.regs.get(dst);
-        self.mem_set(addr, val);
+    // EI schedules IME to be set after the next instruction. Repeating EI doesn't extend the delay.
+    fn enable_interrupts(&mut self) {
+        if self.ime == ImeState::Disabled {
+            self.ime = ImeState::Pending;
+        }
     }
 
-    // Copy value from (HL) into given register.
-    fn get_reg_ptr(&mut self, dst: Reg8, src: Reg16) {
-        let val = self.mem_get(self.regs.get(src));
-        self.regs.set(dst, val);
+    // DI clears IME right away, which also cancels an EI from the previous instruction.
+    fn disable_interrupts(&mut self) {
+        self.ime = ImeState::Disabled;
     }
 
-    // Copy value between A and (HL), then add or subtract HL.
-    fn ldd_special(&mut self, is_set: bool, is_add: bool) {
-        if is_set {
-            self.set_reg_ptr(Reg16::HL, Reg8::A); // LD (HL+/-), A
+    // Increment or decrement a 16-bit register, which takes an extra cycle and doesn't touch flags.
+    fn inc_dec_u16(&mut self, dst: Reg16, inc: bool) {
+        self.cycle();
+        if inc {
+            self.regs.add(dst, 1);
         } else {
-            self.get_reg_ptr(Reg8::A, Reg16::HL); // LD A, (HL+/-)
-        }
-
-        if is_add {
-            self.regs.add(Reg16::HL, 1);
-        } else {
-            self.regs.sub(Reg16::HL, 1);
+            self.regs.sub(dst, 1);
         }
     }
 
@@ -219,72 +343,57 @@ impl CPU {
     fn ld_fast_page(&mut self, is_get: bool) {
         let addr = 0xff00 + self.regs.get(Reg8::C) as u16;
         if is_get {
-            let val = self.mem_get(addr);
+            let val = self.read_cycle(addr);
             self.regs.set(Reg8::A, val);
         } else {
             let val = self.regs.get(Reg8::A);
-            self.mem_set(val, addr);
+            self.write_cycle(val, addr);
         }
     }
 
-    // Write the stack pointer address to memory (two bytes).
-    fn write_sp_to_ptr(&mut self, addr: u16) {
-        let split_addr = util::split_u16(self.regs.get(Reg16::SP));
-        self.mem_set(split_addr.0, addr);
-        self.mem_set(split_addr.1, addr+1);
-    }
-
-    // Increment/decrement for (HL) value.
-    // TODO: We need some massive overhaul to handle (HL) properly. ALU functions should be more
-    // generic. All function calls for the (HL) register should be the same as any other 8-bit reg.
-    fn hl_ptr_inc_dec(&mut self, inc: bool) {
-        let op = match inc {
-            true  => AluOp::Add(false),
-            false => AluOp::Sub(false)
-        };
-        let addr = self.regs.get(Reg16::HL);
-        let operand_a = self.mem_get(addr);
-        let result = self.alu(op, operand_a, 1);
-        self.mem_set(result, addr);
+    // Load fast-page (0xFF00 + d8) value to A, or store A to a fast-page.
+    fn ldh_imm(&mut self, is_get: bool) {
+        let addr = 0xff00 + self.fetch8() as u16;
+        if is_get {
+            let val = self.read_cycle(addr);
+            self.regs.set(Reg8::A, val);
+        } else {
+            let val = self.regs.get(Reg8::A);
+            self.write_cycle(val, addr);
+        }
     }
 
-    // Jump to the given address if Z or CY match what we expect
-    fn jump_flag(&mut self, flag: Flag, if_unset: bool, addr: u16) {
-        let flag_val = match flag {
-            Flag::Z  => self.flag_z,
-            Flag::CY => self.flag_cy,
-            _ => panic!("Can only call jump_flag on Z and CY flags.")
-        };
-
-        if flag_val ^ if_unset {
-            self.regs.set(Reg16::PC, addr);
+    // Load A from an absolute address, or store A to it.
+    fn ld_abs(&mut self, is_get: bool) {
+        let addr = self.fetch16();
+        if is_get {
+            let val = self.read_cycle(addr);
+            self.regs.set(Reg8::A, val);
+        } else {
+            let val = self.regs.get(Reg8::A);
+            self.write_cycle(val, addr);
         }
     }
 
-    // Jump only if flag is set (or unset)
-    fn jump_relative_flag(&mut self, flag: Flag, if_unset: bool, offset: u8) {
-        let flag_val = match flag {
-            Flag::Z  => self.flag_z,
-            Flag::CY => self.flag_cy,
-            _ => panic!("Can only call jump_relative_flag on Z and CY flags.")
-        };
+    // Write the stack pointer address to memory (two bytes).
+    fn write_sp_to_ptr(&mut self, addr: u16) {
+        let split_addr = util::split_u16(self.regs.get(Reg16::SP));
+        self.write_cycle(split_addr.0, addr);
+        self.write_cycle(split_addr.1, addr.wrapping_add(1));
+    }
 
-        if flag_val ^ if_unset {
-            self.jump_relative(offset);
-        }
+    // Jump to an absolute address, loading PC takes an extra cycle.
+    fn jump(&mut self, addr: u16) {
+        self.cycle();
+        self.regs.set(Reg16::PC, addr);
     }
 
-    // Jump relative to current PC, where offset is twos-complement 8-bit signed int.
+    // Jump relative to current PC, where offset is twos-complement 8-bit signed int. PC wraps
+    // around the ends of the address space, just like every other address calculation.
     fn jump_relative(&mut self, offset: u8) {
-        let addr = self.regs.get(Reg16::PC) as i32;
-        let addr = addr + (offset as i8) as i32;
-        if addr < 0 || addr > 0xffff {
-            println!("Fatal error: jumped out-of-bounds!");
-            self.quit = true;
-            return;
-        }
-
-        self.regs.set(Reg16::PC, addr as u16);
+        let addr = self.regs.get(Reg16::PC).wrapping_add((offset as i8) as u16);
+        self.cycle();
+        self.regs.set(Reg16::PC, addr);
     }
 
     // Perform given ALU instruction against the given operands. It's the responsibility of other
@@ -383,60 +492,35 @@ impl CPU {
         result
     }
 
-    // As it turns out, adding/subtracting is really the only 16-bit ALU operation
-    fn add_u16(&mut self, operand_a: u16, operand_b: u16, subtract: bool) -> u16 {
-        match subtract {
-            false => {
-                let add16res = operand_a.overflowing_add(operand_b);
-                self.flag_cy = add16res.1;
-                self.flag_h = ((operand_a & 0xfff) + (operand_b & 0xfff)) > 0xfff;
-                add16res.0
-            },
-            true  => operand_a.wrapping_sub(operand_b)
-        }
+    // As it turns out, adding is really the only 16-bit ALU operation. H and CY are the carries out
+    // of bits 11 and 15.
+    fn add_u16(&mut self, operand_a: u16, operand_b: u16) -> u16 {
+        let add16res = operand_a.overflowing_add(operand_b);
+        self.flag_cy = add16res.1;
+        self.flag_h = ((operand_a & 0xfff) + (operand_b & 0xfff)) > 0xfff;
+        add16res.0
     }
 
-    // Perform ALU op on accumulator and input register, and handle flags.
-    fn arith_op(&mut self, op: AluOp, src: Reg8) {
-        let operand_b = self.regs.get(src);
-        self.arith_imm(op, Reg8::A, operand_b);
-    }
-
-    fn arith_hl_ptr(&mut self, op: AluOp) {
-        let operand_b = self.regs.get(Reg16::HL);
-        let operand_b = self.mem_get(operand_b);
-        self.arith_imm(op, Reg8::A, operand_b);
-    }
-
-    fn bitwise_hl_ptr(&mut self, op: AluOp) {
-        let addr = self.regs.get(Reg16::HL);
-        let operand_a = self.mem_get(addr);
-        let result = self.alu(op, operand_a, 0);
-        self.mem_set(result, addr);
-    }
-
-    // Take an immediate u8 instead of a register.
-    fn arith_imm(&mut self, op: AluOp, dst_reg: Reg8, val: u8) {
-        let operand_a = self.regs.get(dst_reg);
-        let result = self.alu(op, operand_a, val);
-        self.regs.set(dst_reg, result);
-    }
-
-    // Add a 16 bit register to HL
+    // Add a 16 bit register to HL, the ALU takes an extra cycle to do this 8 bits at a time.
     fn add_hl(&mut self, src: Reg16) {
+        self.cycle();
         let operand_a = self.regs.get(Reg16::HL);
         let operand_b = self.regs.get(src);
-        let result = self.add_u16(operand_a, operand_b, false);
+        let result = self.add_u16(operand_a, operand_b);
         self.regs.set(Reg16::HL, result);
     }
 
-    // Add SP and immediate signed, and store to given Reg16.
+    // Add SP and immediate signed, and store to given Reg16. Writing back to SP takes one more
+    // internal cycle than writing to HL. The flags come from an unsigned add of the offset byte to
+    // the low byte of SP, so H and CY are the carries out of bits 3 and 7, whatever the sign.
     fn add_sp_signed(&mut self, dest: Reg16, offset: i8) {
-        let sub = offset < 0;
-        let offset_u = (offset as u8) as u16;
+        self.cycle();
+        if let Reg16::SP = dest { self.cycle(); }
         let sp_val = self.regs.get(Reg16::SP);
-        let sp_val = self.add_u16(sp_val, offset_u, sub);
-        self.regs.set(dest, sp_val);
+        let offset_u = (offset as u8) as u16;
+        self.flag_h = (sp_val & 0xf) + (offset_u & 0xf) > 0xf;
+        self.flag_cy = (sp_val & 0xff) + offset_u > 0xff;
+        self.regs.set(dest, sp_val.wrapping_add(offset as u16));
     }
 
     // We modify a local copy of each register value, then sync them using this function after the
@@ -490,32 +574,47 @@ impl CPU {
         }
     }
 
+    // Correct A back to packed BCD after an add or subtract of two BCD values. N, H and CY from
+    // that operation tell us which digits need adjusting. CY is only ever set here, never cleared.
     fn decimal_adjust(&mut self) {
-        let lo = self.regs.get(Reg8::A);
-        let hi = lo.wrapping_shl(4);
-        let lo = lo & 0xF;
+        let val = self.regs.get(Reg8::A);
         let mut adjust = 0;
         if !self.flag_n {
-            if self.flag_cy || hi > 0x9 || lo > 0x9 {
-                adjust += 0x60;
+            if self.flag_h || (val & 0xf) > 0x9 {
+                adjust |= 0x06;
             }
-            if self.flag_h || lo > 0x9 {
-                adjust += 0x6;
+            if self.flag_cy || val > 0x99 {
+                adjust |= 0x60;
+                self.flag_cy = true;
             }
         } else {
+            if self.flag_h {
+                adjust |= 0x06;
+            }
             if self.flag_cy {
-                if self.flag_h {
-                    adjust += 0x9a;
-                } else {
-                    adjust += 0xa0;
-                }
-            } else if self.flag_h {
-                adjust += 0xfa;
+                adjust |= 0x60;
             }
         }
 
-        // TODO: arith_imm modifies flags, but should this instruction be doing that? look up
-        self.arith_imm(AluOp::Add(false), Reg8::A, adjust);
+        let val = if self.flag_n { val.wrapping_sub(adjust) } else { val.wrapping_add(adjust) };
+        self.flag_z = val == 0;
+        self.regs.set(Reg8::A, val);
+    }
+
+    // LD B,B does nothing, so test ROMs like Mooneye's execute it as a breakpoint once they're done.
+    fn magic_breakpoint(&mut self) {
+        if self.magic_breaks {
+            self.hit_magic_break = true;
+        }
+    }
+
+    // Reload the local flag copies from F, for when the registers have been changed from outside
+    // of an instruction.
+    fn load_flags(&mut self) {
+        self.flag_z = self.regs.get_flag(Flag::Z);
+        self.flag_n = self.regs.get_flag(Flag::N);
+        self.flag_h = self.regs.get_flag(Flag::H);
+        self.flag_cy = self.regs.get_flag(Flag::CY);
     }
 
     // Toggle the CY flag, used for CCF instruction
@@ -523,615 +622,330 @@ impl CPU {
         self.flag_cy = !self.flag_cy;
     }
 
-    // For HALT, just exit the program for now. TODO: Add accurate HALT emulation here.
+    // HALT stops fetching instructions until an enabled interrupt is requested. If IME isn't set
+    // and an interrupt is already pending, the CPU doesn't halt at all. Instead it hits the HALT
+    // bug: PC fails to increment after the next opcode fetch, so that byte is read twice.
     fn halt(&mut self) {
-        println!("Encountered HALT instruction, exiting!");
-        self.quit = true;
+        if self.ime != ImeState::Enabled && self.pending_interrupts() != 0 {
+            self.halt_bug = true;
+        } else {
+            self.halted = true;
+        }
     }
 
+    // STOP either performs a CGB speed switch, if one was prepared through KEY1, or stops the
+    // system clock until a button is pressed.
     fn stop(&mut self) {
-        println!("Encountered STOP instruction, exiting!");
-        self.quit = true;
+        // STOP is followed by a padding byte, which is skipped over. It also resets DIV.
+        self.regs.add(Reg16::PC, 1);
+        self.mem_set(0x00, DIV_ADDR);
+
+        if self.cgb && (self.mem_get(KEY1_ADDR) & 0x1) != 0 {
+            self.double_speed = !self.double_speed;
+            let mut mref = self.mem.lock().unwrap();
+            (*mref).set_double_speed(self.double_speed);
+            if self.verbose {
+                println!("Switched CPU to {} speed", if self.double_speed { "double" } else { "normal" });
+            }
+        } else {
+            self.stopped = true;
+        }
     }
 
-    // Run the LCD, then process the current instruction.
-    // TODO: This should eventually be cycle-accurate
-    pub fn tick(&mut self) -> bool {
-        self.ppu.tick();
+    // Illegal opcodes hang the CPU for good. Only a reset gets it going again, but the rest of the
+    // system keeps running, so hand control to the debugger to inspect what went wrong. Headless
+    // runs have nobody at the prompt, so they just report it and leave the runner to stop.
+    fn lock_up(&mut self, pc: u16, opcode: u8) {
+        self.locked = true;
+        if self.headless {
+            println!("{} at 0x{:04x}", BreakReason::Lockup(opcode), pc);
+        } else {
+            self.enter_debugger(pc, BreakReason::Lockup(opcode));
+        }
+    }
+
+    // A pressed button pulls its P1 input line low, for whichever button groups are selected.
+    fn joypad_pressed(&self) -> bool {
+        (self.mem_get(P1_ADDR) & 0xF) != 0xF
+    }
+
+    // Run the peripherals for one CPU machine cycle. OAM DMA is clocked with the CPU, but in CGB double
+    // speed mode the CPU runs twice as fast as the PPU, so it only advances on every other cycle.
+    fn tick_peripherals(&mut self) {
+        {
+            let mut mref = self.mem.lock().unwrap();
+            (*mref).tick();
+        }
+
+        self.slow_cycle = !self.slow_cycle;
+        if !self.double_speed || self.slow_cycle {
+            self.ppu.tick();
+        }
+    }
+
+    // Return the interrupts that are both enabled in IE and requested in IF.
+    fn pending_interrupts(&self) -> u8 {
+        self.mem_get(interrupt::IE_ADDR) & self.mem_get(interrupt::IF_ADDR) & 0x1F
+    }
+
+    // If IME is set and an enabled interrupt has been requested, disable IME, acknowledge the
+    // interrupt in IF, and call its vector. Returns true if an interrupt was dispatched.
+    fn service_interrupt(&mut self) -> bool {
+        if self.ime != ImeState::Enabled { return false; }
+
+        let ie = self.mem_get(interrupt::IE_ADDR);
+        let iflag = self.mem_get(interrupt::IF_ADDR);
+        let ir = match Interrupt::highest_pending(ie, iflag) {
+            Some(ir) => ir,
+            None => return false,
+        };
+
+        if self.verbose {
+            println!("Servicing {} interrupt, jumping to 0x{:04x}", ir, ir.vector());
+        }
+
+        // EI followed by a bugged HALT returns from the handler to the HALT, instead of after it.
+        if self.halt_bug {
+            self.regs.sub(Reg16::PC, 1);
+            self.halt_bug = false;
+        }
+
+        self.ime = ImeState::Disabled;
+        self.mem_set(iflag & !ir.mask(), interrupt::IF_ADDR);
+
+        // Dispatch takes five machine cycles: two wait states, two to push PC to the stack, and one
+        // to load the interrupt vector into PC.
+        self.cycle();
+        self.cycle();
+        let pc = self.regs.get(Reg16::PC);
+        self.push_u16(pc);
+        self.jump(ir.vector());
+
+        true
+    }
 
+    // Process the current instruction. The LCD and other peripherals are run in between each of the
+    // instruction's memory accesses, so they stay in step with the CPU.
+    pub fn tick(&mut self) -> bool {
         if !self.ppu.is_alive() {
             println!("Closed PPU window!");
-            false
-        } else {
-            self.process()
+            return false;
+        }
+
+        self.process();
+        self.clocks += self.system_clocks(self.mcycles);
+        if self.ppu.take_debug_request() {
+            self.stepinto = true;
         }
+
+        // In STOP mode the system clock is halted, so only the window is serviced.
+        if self.stopped {
+            self.ppu.idle();
+        }
+
+        !self.quit
     }
 
-    // Run the instruction at the current PC, return true if successful.
-    pub fn process(&mut self) -> bool {
-        if self.quit { return false; }
-        let old_pc = self.regs.get(Reg16::PC);
-        let opcode = self.mem_get(old_pc);
-        let _operand8  = self.mem_get(old_pc+1);
-        let _operand16 = self.parse_u16(old_pc+1);
-
-        // Adjust opcode if it's a 0xcb prefixed instruction
-        let opcode = if opcode == 0xcb {
-            let newop = ((0xcb as u16) << 8) | _operand8 as u16;
-            let _operand8  = self.mem_get(old_pc+2);
-            let _operand16 = self.parse_u16(old_pc+2);
-            newop
-        } else {
-            opcode as u16
-        };
+    // True while the system clock is stopped by STOP, waiting for a button press.
+    pub fn is_stopped(&self) -> bool {
+        self.stopped
+    }
+
+    // True once an illegal opcode has locked up the CPU.
+    pub fn is_locked(&self) -> bool {
+        self.locked
+    }
 
-        self.inst = lookup::get_instruction(opcode);
-        self.flagmod = lookup::get_flagmod(opcode);
+    // Returns true once after LD B,B has been executed, if magic breakpoints are enabled.
+    pub fn take_magic_break(&mut self) -> bool {
+        std::mem::replace(&mut self.hit_magic_break, false)
+    }
 
-        // TODO: Check here to see if we need to process an interrupt
+    // Total system clocks run since power on. These tick at 4 MiHz whatever speed the CPU runs at,
+    // so they keep track of emulated time in double speed mode too.
+    pub fn clocks(&self) -> u64 {
+        self.clocks
+    }
+
+    // The system clocks taken by this many CPU machine cycles. In double speed mode a machine cycle
+    // is only two system clocks long.
+    fn system_clocks(&self, mcycles: u32) -> u64 {
+        mcycles as u64 * if self.double_speed { 2 } else { 4 }
+    }
+
+    // Run the instruction at the current PC, and return the number of clocks it took.
+    pub fn process(&mut self) -> u32 {
+        self.mcycles = 0;
+        if self.quit { return 0; }
+
+        // A locked up CPU never fetches another instruction, and can't be woken by interrupts. The
+        // debugger can still be entered from the hotkey, or by stepping, to look around.
+        if self.locked {
+            self.cycle();
+            if std::mem::take(&mut self.stepinto) {
+                let pc = self.inst_pc;
+                self.enter_debugger(pc, BreakReason::Lockup(self.mem_get(pc)));
+            }
+            return self.mcycles * 4;
+        }
+
+        // STOP mode is only left when a button is pressed.
+        if self.stopped {
+            if !self.joypad_pressed() { return 0; }
+            self.stopped = false;
+        }
+
+        // While halted the CPU idles for a machine cycle at a time, and the rest of the system
+        // keeps running. Any enabled interrupt wakes it up, even if IME is off.
+        if self.halted {
+            if self.pending_interrupts() == 0 {
+                self.cycle();
+                return self.mcycles * 4;
+            }
+            self.halted = false;
+        }
+
+        // Interrupts are checked between instructions, dispatching one replaces this step.
+        if self.service_interrupt() { return self.mcycles * 4; }
+
+        if self.trace.is_some() { self.write_trace(); }
+
+        // Fetch the opcode, from the block cache if it's enabled. The HALT bug rereads the opcode
+        // byte, which the cache can't do, so it's left to the normal fetch.
+        let old_pc = self.regs.get(Reg16::PC);
+        let cached = match &mut self.blocks {
+            Some(blocks) if !self.halt_bug => blocks.lookup(old_pc, self.clocks, &self.mem),
+            _ => None,
+        };
+        let opcode = match cached {
+            Some(decoded) => {
+                self.inst = decoded.inst;
+                self.flagmod = decoded.flagmod;
+                self.fetch_cached(&decoded)
+            },
+            None => {
+                let opcode = self.fetch_opcode(old_pc);
+                self.inst = lookup::get_instruction(opcode);
+                self.flagmod = lookup::get_flagmod(opcode);
+                opcode
+            },
+        };
 
         // Handle debugging here
         self.handle_debugging(old_pc);
-        if self.quit { return false; }
-
-        // Increment PC before we process the instruction. During execution the current PC will
-        // represent the next instruction to process.
-        self.regs.set(Reg16::PC, old_pc + (self.inst.bytes as u16));
-
-        match opcode {
-            // [0x00, 0x3f] - Load, INC/DEC, some jumps, and other various instructions.
-            0x00 => (),
-            0x01 => self.regs.set(Reg16::BC, _operand16),
-            0x02 => self.set_reg_ptr(Reg16::BC, Reg8::A),
-            0x03 => self.regs.add(Reg16::BC, 1),
-            0x04 => self.arith_imm(AluOp::Add(false), Reg8::B, 1),
-            0x05 => self.arith_imm(AluOp::Sub(false), Reg8::B, 1),
-            0x06 => self.regs.set(Reg8::B, _operand8),
-            0x07 => self.arith_imm(AluOp::RotateLeft(true), Reg8::A, 0),
-            0x08 => self.write_sp_to_ptr(_operand16),
-            0x09 => self.add_hl(Reg16::BC),
-            0x0a => self.get_reg_ptr(Reg8::A, Reg16::BC),
-            0x0b => self.regs.sub(Reg16::BC, 1),
-            0x0c => self.arith_imm(AluOp::Add(false), Reg8::C, 1),
-            0x0d => self.arith_imm(AluOp::Sub(false), Reg8::C, 1),
-            0x0e => self.regs.set(Reg8::C, _operand8),
-            0x0f => self.arith_imm(AluOp::RotateRight(true), Reg8::A, 0),
-            0x10 => self.stop(),
-            0x11 => self.regs.set(Reg16::DE, _operand16),
-            0x12 => self.set_reg_ptr(Reg16::DE, Reg8::A),
-            0x13 => self.regs.add(Reg16::DE, 1),
-            0x14 => self.arith_imm(AluOp::Add(false), Reg8::D, 1),
-            0x15 => self.arith_imm(AluOp::Sub(false), Reg8::D, 1),
-            0x16 => self.regs.set(Reg8::D, _operand8),
-            0x17 => self.arith_imm(AluOp::RotateLeft(false), Reg8::A, 0),
-            0x18 => self.jump_relative(_operand8),
-            0x19 => self.add_hl(Reg16::DE),
-            0x1a => self.get_reg_ptr(Reg8::A, Reg16::DE),
-            0x1b => self.regs.sub(Reg16::BC, 1),
-            0x1c => self.arith_imm(AluOp::Add(false), Reg8::E, 1),
-            0x1d => self.arith_imm(AluOp::Sub(false), Reg8::E, 1),
-            0x1e => self.regs.set(Reg8::E, _operand8),
-            0x1f => self.arith_imm(AluOp::RotateRight(false), Reg8::A, 0),
-            0x20 => self.jump_relative_flag(Flag::Z, true, _operand8),
-            0x21 => self.regs.set(Reg16::HL, _operand16),
-            0x22 => self.ldd_special(true, true),
-            0x23 => self.regs.add(Reg16::HL, 1),
-            0x24 => self.arith_imm(AluOp::Add(false), Reg8::H, 1),
-            0x25 => self.arith_imm(AluOp::Sub(false), Reg8::H, 1),
-            0x26 => self.regs.set(Reg8::H, _operand8),
-            0x27 => self.decimal_adjust(),
-            0x28 => self.jump_relative_flag(Flag::Z, false, _operand8),
-            0x29 => self.add_hl(Reg16::HL),
-            0x2a => self.ldd_special(false, true),
-            0x2b => self.regs.sub(Reg16::HL, 1),
-            0x2c => self.arith_imm(AluOp::Add(false), Reg8::L, 1),
-            0x2d => self.arith_imm(AluOp::Sub(false), Reg8::L, 1),
-            0x2e => self.regs.set(Reg8::L, _operand8),
-            0x2f => self.arith_imm(AluOp::Xor, Reg8::A, 0xff),
-            0x30 => self.jump_relative_flag(Flag::CY, true, _operand8),
-            0x31 => self.regs.set(Reg16::SP, _operand16),
-            0x32 => self.ldd_special(true, false),
-            0x33 => self.regs.add(Reg16::SP, 1),
-            0x34 => self.hl_ptr_inc_dec(true),
-            0x35 => self.hl_ptr_inc_dec(false),
-            0x36 => {let hl = self.regs.get(Reg16::HL); self.mem_set(_operand8, hl)},
-            0x37 => (), // Handled in the upcoming call to sync_flags
-            0x38 => self.jump_relative_flag(Flag::CY, false, _operand8),
-            0x39 => self.add_hl(Reg16::SP),
-            0x3a => self.ldd_special(false, false),
-            0x3b => self.regs.sub(Reg16::SP, 1),
-            0x3c => self.arith_imm(AluOp::Add(false), Reg8::A, 1),
-            0x3d => self.arith_imm(AluOp::Sub(false), Reg8::A, 1),
-            0x3e => self.regs.set(Reg8::A, _operand8),
-            0x3f => self.toggle_cy(),
-
-            // [0x40, 0x7f] - Mostly copy instructions between registers and (HL).
-            0x40 => self.regs.copy(Reg8::B, Reg8::B),
-            0x41 => self.regs.copy(Reg8::B, Reg8::C),
-            0x42 => self.regs.copy(Reg8::B, Reg8::D),
-            0x43 => self.regs.copy(Reg8::B, Reg8::E),
-            0x44 => self.regs.copy(Reg8::B, Reg8::H),
-            0x45 => self.regs.copy(Reg8::B, Reg8::L),
-            0x46 => self.get_reg_ptr(Reg8::B, Reg16::HL),
-            0x47 => self.regs.copy(Reg8::B, Reg8::A),
-            0x48 => self.regs.copy(Reg8::C, Reg8::B),
-            0x49 => self.regs.copy(Reg8::C, Reg8::C),
-            0x4a => self.regs.copy(Reg8::C, Reg8::D),
-            0x4b => self.regs.copy(Reg8::C, Reg8::E),
-            0x4c => self.regs.copy(Reg8::C, Reg8::H),
-            0x4d => self.regs.copy(Reg8::C, Reg8::L),
-            0x4e => self.get_reg_ptr(Reg8::C, Reg16::HL),
-            0x4f => self.regs.copy(Reg8::C, Reg8::A),
-            0x50 => self.regs.copy(Reg8::D, Reg8::B),
-            0x51 => self.regs.copy(Reg8::D, Reg8::C),
-            0x52 => self.regs.copy(Reg8::D, Reg8::D),
-            0x53 => self.regs.copy(Reg8::D, Reg8::E),
-            0x54 => self.regs.copy(Reg8::D, Reg8::H),
-            0x55 => self.regs.copy(Reg8::D, Reg8::L),
-            0x56 => self.get_reg_ptr(Reg8::D, Reg16::HL),
-            0x57 => self.regs.copy(Reg8::D, Reg8::A),
-            0x58 => self.regs.copy(Reg8::E, Reg8::B),
-            0x59 => self.regs.copy(Reg8::E, Reg8::C),
-            0x5a => self.regs.copy(Reg8::E, Reg8::D),
-            0x5b => self.regs.copy(Reg8::E, Reg8::E),
-            0x5c => self.regs.copy(Reg8::E, Reg8::H),
-            0x5d => self.regs.copy(Reg8::E, Reg8::L),
-            0x5e => self.get_reg_ptr(Reg8::E, Reg16::HL),
-            0x5f => self.regs.copy(Reg8::E, Reg8::A),
-            0x60 => self.regs.copy(Reg8::H, Reg8::B),
-            0x61 => self.regs.copy(Reg8::H, Reg8::C),
-            0x62 => self.regs.copy(Reg8::H, Reg8::D),
-            0x63 => self.regs.copy(Reg8::H, Reg8::E),
-            0x64 => self.regs.copy(Reg8::H, Reg8::H),
-            0x65 => self.regs.copy(Reg8::H, Reg8::L),
-            0x66 => self.get_reg_ptr(Reg8::H, Reg16::HL),
-            0x67 => self.regs.copy(Reg8::H, Reg8::A),
-            0x68 => self.regs.copy(Reg8::L, Reg8::B),
-            0x69 => self.regs.copy(Reg8::L, Reg8::C),
-            0x6a => self.regs.copy(Reg8::L, Reg8::D),
-            0x6b => self.regs.copy(Reg8::L, Reg8::E),
-            0x6c => self.regs.copy(Reg8::L, Reg8::H),
-            0x6d => self.regs.copy(Reg8::L, Reg8::L),
-            0x6e => self.get_reg_ptr(Reg8::L, Reg16::HL),
-            0x6f => self.regs.copy(Reg8::L, Reg8::A),
-            0x70 => self.set_reg_ptr(Reg16::HL, Reg8::B),
-            0x71 => self.set_reg_ptr(Reg16::HL, Reg8::C),
-            0x72 => self.set_reg_ptr(Reg16::HL, Reg8::D),
-            0x73 => self.set_reg_ptr(Reg16::HL, Reg8::E),
-            0x74 => self.set_reg_ptr(Reg16::HL, Reg8::H),
-            0x75 => self.set_reg_ptr(Reg16::HL, Reg8::L),
-            0x76 => self.halt(),
-            0x77 => self.set_reg_ptr(Reg16::HL, Reg8::A),
-            0x78 => self.regs.copy(Reg8::A, Reg8::B),
-            0x79 => self.regs.copy(Reg8::A, Reg8::C),
-            0x7a => self.regs.copy(Reg8::A, Reg8::D),
-            0x7b => self.regs.copy(Reg8::A, Reg8::E),
-            0x7c => self.regs.copy(Reg8::A, Reg8::H),
-            0x7d => self.regs.copy(Reg8::A, Reg8::L),
-            0x7e => self.get_reg_ptr(Reg8::A, Reg16::HL),
-            0x7f => self.regs.copy(Reg8::A, Reg8::A),
-
-            // [0x80, 0xbf] - Arithmetic operations
-            0x80 => self.arith_op(AluOp::Add(false), Reg8::B),
-            0x81 => self.arith_op(AluOp::Add(false), Reg8::C),
-            0x82 => self.arith_op(AluOp::Add(false), Reg8::D),
-            0x83 => self.arith_op(AluOp::Add(false), Reg8::E),
-            0x84 => self.arith_op(AluOp::Add(false), Reg8::H),
-            0x85 => self.arith_op(AluOp::Add(false), Reg8::L),
-            0x86 => self.arith_hl_ptr(AluOp::Add(false)),
-            0x87 => self.arith_op(AluOp::Add(false), Reg8::A),
-            0x88 => self.arith_op(AluOp::Add(true), Reg8::B),
-            0x89 => self.arith_op(AluOp::Add(true), Reg8::C),
-            0x8a => self.arith_op(AluOp::Add(true), Reg8::D),
-            0x8b => self.arith_op(AluOp::Add(true), Reg8::E),
-            0x8c => self.arith_op(AluOp::Add(true), Reg8::H),
-            0x8d => self.arith_op(AluOp::Add(true), Reg8::L),
-            0x8e => self.arith_hl_ptr(AluOp::Add(true)),
-            0x8f => self.arith_op(AluOp::Add(true), Reg8::A),
-            0x90 => self.arith_op(AluOp::Sub(false), Reg8::B),
-            0x91 => self.arith_op(AluOp::Sub(false), Reg8::C),
-            0x92 => self.arith_op(AluOp::Sub(false), Reg8::D),
-            0x93 => self.arith_op(AluOp::Sub(false), Reg8::E),
-            0x94 => self.arith_op(AluOp::Sub(false), Reg8::H),
-            0x95 => self.arith_op(AluOp::Sub(false), Reg8::L),
-            0x96 => self.arith_hl_ptr(AluOp::Sub(false)),
-            0x97 => self.arith_op(AluOp::Sub(false), Reg8::A),
-            0x98 => self.arith_op(AluOp::Sub(true), Reg8::B),
-            0x99 => self.arith_op(AluOp::Sub(true), Reg8::C),
-            0x9a => self.arith_op(AluOp::Sub(true), Reg8::D),
-            0x9b => self.arith_op(AluOp::Sub(true), Reg8::E),
-            0x9c => self.arith_op(AluOp::Sub(true), Reg8::H),
-            0x9d => self.arith_op(AluOp::Sub(true), Reg8::L),
-            0x9e => self.arith_hl_ptr(AluOp::Sub(true)),
-            0x9f => self.arith_op(AluOp::Sub(true), Reg8::A),
-            0xa0 => self.arith_op(AluOp::And, Reg8::B),
-            0xa1 => self.arith_op(AluOp::And, Reg8::C),
-            0xa2 => self.arith_op(AluOp::And, Reg8::D),
-            0xa3 => self.arith_op(AluOp::And, Reg8::E),
-            0xa4 => self.arith_op(AluOp::And, Reg8::H),
-            0xa5 => self.arith_op(AluOp::And, Reg8::L),
-            0xa6 => self.arith_hl_ptr(AluOp::And),
-            0xa7 => self.arith_op(AluOp::And, Reg8::A),
-            0xa8 => self.arith_op(AluOp::Xor, Reg8::B),
-            0xa9 => self.arith_op(AluOp::Xor, Reg8::C),
-            0xaa => self.arith_op(AluOp::Xor, Reg8::D),
-            0xab => self.arith_op(AluOp::Xor, Reg8::E),
-            0xac => self.arith_op(AluOp::Xor, Reg8::H),
-            0xad => self.arith_op(AluOp::Xor, Reg8::L),
-            0xae => self.arith_hl_ptr(AluOp::Xor),
-            0xaf => self.arith_op(AluOp::Xor, Reg8::A),
-            0xb0 => self.arith_op(AluOp::Or, Reg8::B),
-            0xb1 => self.arith_op(AluOp::Or, Reg8::C),
-            0xb2 => self.arith_op(AluOp::Or, Reg8::D),
-            0xb3 => self.arith_op(AluOp::Or, Reg8::E),
-            0xb4 => self.arith_op(AluOp::Or, Reg8::H),
-            0xb5 => self.arith_op(AluOp::Or, Reg8::L),
-            0xb6 => self.arith_hl_ptr(AluOp::Or),
-            0xb7 => self.arith_op(AluOp::Or, Reg8::A),
-            0xb8 => self.arith_op(AluOp::Comp, Reg8::B),
-            0xb9 => self.arith_op(AluOp::Comp, Reg8::C),
-            0xba => self.arith_op(AluOp::Comp, Reg8::D),
-            0xbb => self.arith_op(AluOp::Comp, Reg8::E),
-            0xbc => self.arith_op(AluOp::Comp, Reg8::H),
-            0xbd => self.arith_op(AluOp::Comp, Reg8::L),
-            0xbe => self.arith_hl_ptr(AluOp::Comp),
-            0xbf => self.arith_op(AluOp::Comp, Reg8::A),
-
-            // [0xc0, 0xff] - Flow control, push/pop/call/ret, and other various instructions.
-            0xc0 => self.ret_flag(Flag::Z, true),
-            0xc1 => self.pop(Reg16::BC),
-            0xc2 => self.jump_flag(Flag::Z, true, _operand16),
-            0xc3 => self.regs.set(Reg16::PC, _operand16),
-            0xc4 => self.call_flag(Flag::Z, true, _operand16),
-            0xc5 => self.push(Reg16::BC),
-            0xc6 => self.arith_imm(AluOp::Add(false), Reg8::A, _operand8),
-            0xc7 => self.call(0x00),
-            0xc8 => self.ret_flag(Flag::Z, false),
-            0xc9 => self.ret(false),
-            0xca => self.jump_flag(Flag::Z, false, _operand16),
-            0xcb => self.quit = true, // This shouldn't ever happen
-            0xcc => self.call_flag(Flag::Z, false, _operand16),
-            0xcd => self.call(_operand16),
-            0xce => self.arith_imm(AluOp::Add(true), Reg8::A, _operand8),
-            0xcf => self.call(0x08),
-            0xd0 => self.ret_flag(Flag::CY, true),
-            0xd1 => self.pop(Reg16::DE),
-            0xd2 => self.jump_flag(Flag::CY, true, _operand16),
-            0xd3 => panic!("Received invalid instruction UNKNOWN_{:02X}", opcode),
-            0xd4 => self.call_flag(Flag::CY, true, _operand16),
-            0xd5 => self.push(Reg16::DE),
-            0xd6 => self.arith_imm(AluOp::Sub(false), Reg8::A, _operand8),
-            0xd7 => self.call(0x10),
-            0xd8 => self.ret_flag(Flag::CY, false),
-            0xd9 => self.ret(true),
-            0xda => self.jump_flag(Flag::CY, false, _operand16),
-            0xdb => panic!("Received invalid instruction UNKNOWN_{:02X}", opcode),
-            0xdc => self.call_flag(Flag::CY, false, _operand16),
-            0xdd => panic!("Received invalid instruction UNKNOWN_{:02X}", opcode),
-            0xde => self.arith_imm(AluOp::Sub(true), Reg8::A, _operand8),
-            0xdf => self.call(0x18),
-            0xe0 => {let a = self.regs.get(Reg8::A); self.mem_set(a, 0xff00 + (_operand8 as u16))},
-            0xe1 => self.pop(Reg16::HL),
-            0xe2 => self.ld_fast_page(true),
-            0xe3 => panic!("Received invalid instruction UNKNOWN_{:02X}", opcode),
-            0xe4 => panic!("Received invalid instruction UNKNOWN_{:02X}", opcode),
-            0xe5 => self.push(Reg16::HL),
-            0xe6 => self.arith_imm(AluOp::And, Reg8::A, _operand8),
-            0xe7 => self.call(0x20),
-            0xe8 => self.add_sp_signed(Reg16::SP, _operand8 as i8),
-            0xe9 => {let a = self.regs.get(Reg16::HL); self.regs.set(Reg16::PC, a); },
-            0xea => {let a = self.regs.get(Reg8::A); self.mem_set(a, _operand16)},
-            0xeb => panic!("Received invalid instruction UNKNOWN_{:02X}", opcode),
-            0xec => panic!("Received invalid instruction UNKNOWN_{:02X}", opcode),
-            0xed => panic!("Received invalid instruction UNKNOWN_{:02X}", opcode),
-            0xee => self.arith_imm(AluOp::Xor, Reg8::A, _operand8),
-            0xef => self.call(0x28),
-            0xf0 => {let val = self.mem_get(0xff00 + (_operand8 as u16)); self.regs.set(Reg8::A, val)},
-            0xf1 => self.pop(Reg16::AF),
-            0xf2 => self.ld_fast_page(false),
-            0xf3 => self.ir_enabled = false,
-            0xf4 => panic!("Received invalid instruction UNKNOWN_{:02X}", opcode),
-            0xf5 => self.push(Reg16::AF),
-            0xf6 => self.arith_imm(AluOp::Or, Reg8::A, _operand8),
-            0xf7 => self.call(0x30),
-            0xf8 => self.add_sp_signed(Reg16::HL, _operand8 as i8),
-            0xf9 => self.regs.copy(Reg16::SP, Reg16::HL),
-            0xfa => {let val = self.mem_get(_operand16); self.regs.set(Reg8::A, val)},
-            0xfb => self.ir_enabled = true,
-            0xfc => panic!("Received invalid instruction UNKNOWN_{:02X}", opcode),
-            0xfd => panic!("Received invalid instruction UNKNOWN_{:02X}", opcode),
-            0xfe => self.arith_imm(AluOp::Comp, Reg8::A, _operand8),
-            0xff => self.call(0x38),
-
-            // [0xcb00, 0xcb3f] - Bitwise rotate, shift, and swap.
-            0xcb00 => self.arith_imm(AluOp::RotateLeft(true), Reg8::B, 0),
-            0xcb01 => self.arith_imm(AluOp::RotateLeft(true), Reg8::C, 0),
-            0xcb02 => self.arith_imm(AluOp::RotateLeft(true), Reg8::D, 0),
-            0xcb03 => self.arith_imm(AluOp::RotateLeft(true), Reg8::E, 0),
-            0xcb04 => self.arith_imm(AluOp::RotateLeft(true), Reg8::H, 0),
-            0xcb05 => self.arith_imm(AluOp::RotateLeft(true), Reg8::L, 0),
-            0xcb06 => self.bitwise_hl_ptr(AluOp::RotateLeft(true)),
-            0xcb07 => self.arith_imm(AluOp::RotateLeft(true), Reg8::A, 0),
-            0xcb08 => self.arith_imm(AluOp::RotateRight(true), Reg8::B, 0),
-            0xcb09 => self.arith_imm(AluOp::RotateRight(true), Reg8::C, 0),
-            0xcb0a => self.arith_imm(AluOp::RotateRight(true), Reg8::D, 0),
-            0xcb0b => self.arith_imm(AluOp::RotateRight(true), Reg8::E, 0),
-            0xcb0c => self.arith_imm(AluOp::RotateRight(true), Reg8::H, 0),
-            0xcb0d => self.arith_imm(AluOp::RotateRight(true), Reg8::L, 0),
-            0xcb0e => self.bitwise_hl_ptr(AluOp::RotateRight(true)),
-            0xcb0f => self.arith_imm(AluOp::RotateRight(true), Reg8::A, 0),
-            0xcb10 => self.arith_imm(AluOp::RotateLeft(false), Reg8::B, 0),
-            0xcb11 => self.arith_imm(AluOp::RotateLeft(false), Reg8::C, 0),
-            0xcb12 => self.arith_imm(AluOp::RotateLeft(false), Reg8::D, 0),
-            0xcb13 => self.arith_imm(AluOp::RotateLeft(false), Reg8::E, 0),
-            0xcb14 => self.arith_imm(AluOp::RotateLeft(false), Reg8::H, 0),
-            0xcb15 => self.arith_imm(AluOp::RotateLeft(false), Reg8::L, 0),
-            0xcb16 => self.bitwise_hl_ptr(AluOp::RotateLeft(false)),
-            0xcb17 => self.arith_imm(AluOp::RotateLeft(false), Reg8::A, 0),
-            0xcb18 => self.arith_imm(AluOp::RotateRight(false), Reg8::B, 0),
-            0xcb19 => self.arith_imm(AluOp::RotateRight(false), Reg8::C, 0),
-            0xcb1a => self.arith_imm(AluOp::RotateRight(false), Reg8::D, 0),
-            0xcb1b => self.arith_imm(AluOp::RotateRight(false), Reg8::E, 0),
-            0xcb1c => self.arith_imm(AluOp::RotateRight(false), Reg8::H, 0),
-            0xcb1d => self.arith_imm(AluOp::RotateRight(false), Reg8::L, 0),
-            0xcb1e => self.bitwise_hl_ptr(AluOp::RotateRight(false)),
-            0xcb1f => self.arith_imm(AluOp::RotateRight(false), Reg8::A, 0),
-            0xcb20 => self.arith_imm(AluOp::ShiftLeft, Reg8::B, 0),
-            0xcb21 => self.arith_imm(AluOp::ShiftLeft, Reg8::C, 0),
-            0xcb22 => self.arith_imm(AluOp::ShiftLeft, Reg8::D, 0),
-            0xcb23 => self.arith_imm(AluOp::ShiftLeft, Reg8::E, 0),
-            0xcb24 => self.arith_imm(AluOp::ShiftLeft, Reg8::H, 0),
-            0xcb25 => self.arith_imm(AluOp::ShiftLeft, Reg8::L, 0),
-            0xcb26 => self.bitwise_hl_ptr(AluOp::ShiftLeft),
-            0xcb27 => self.arith_imm(AluOp::ShiftLeft, Reg8::A, 0),
-            0xcb28 => self.arith_imm(AluOp::ShiftRight(true), Reg8::B, 0),
-            0xcb29 => self.arith_imm(AluOp::ShiftRight(true), Reg8::C, 0),
-            0xcb2a => self.arith_imm(AluOp::ShiftRight(true), Reg8::D, 0),
-            0xcb2b => self.arith_imm(AluOp::ShiftRight(true), Reg8::E, 0),
-            0xcb2c => self.arith_imm(AluOp::ShiftRight(true), Reg8::H, 0),
-            0xcb2d => self.arith_imm(AluOp::ShiftRight(true), Reg8::L, 0),
-            0xcb2e => self.bitwise_hl_ptr(AluOp::ShiftRight(true)),
-            0xcb2f => self.arith_imm(AluOp::ShiftRight(true), Reg8::A, 0),
-            0xcb30 => self.arith_imm(AluOp::Swap, Reg8::B, 0),
-            0xcb31 => self.arith_imm(AluOp::Swap, Reg8::C, 0),
-            0xcb32 => self.arith_imm(AluOp::Swap, Reg8::D, 0),
-            0xcb33 => self.arith_imm(AluOp::Swap, Reg8::E, 0),
-            0xcb34 => self.arith_imm(AluOp::Swap, Reg8::H, 0),
-            0xcb35 => self.arith_imm(AluOp::Swap, Reg8::L, 0),
-            0xcb36 => self.bitwise_hl_ptr(AluOp::Swap),
-            0xcb37 => self.arith_imm(AluOp::Swap, Reg8::A, 0),
-            0xcb38 => self.arith_imm(AluOp::ShiftRight(false), Reg8::B, 0),
-            0xcb39 => self.arith_imm(AluOp::ShiftRight(false), Reg8::C, 0),
-            0xcb3a => self.arith_imm(AluOp::ShiftRight(false), Reg8::D, 0),
-            0xcb3b => self.arith_imm(AluOp::ShiftRight(false), Reg8::E, 0),
-            0xcb3c => self.arith_imm(AluOp::ShiftRight(false), Reg8::H, 0),
-            0xcb3d => self.arith_imm(AluOp::ShiftRight(false), Reg8::L, 0),
-            0xcb3e => self.bitwise_hl_ptr(AluOp::ShiftRight(false)),
-            0xcb3f => self.arith_imm(AluOp::ShiftRight(false), Reg8::A, 0),
-
-            // [0xcb40, 0xcb7f] - Bit test, push value to Z flag
-            0xcb40 => self.arith_imm(AluOp::Test(0), Reg8::B, 0),
-            0xcb41 => self.arith_imm(AluOp::Test(0), Reg8::C, 0),
-            0xcb42 => self.arith_imm(AluOp::Test(0), Reg8::D, 0),
-            0xcb43 => self.arith_imm(AluOp::Test(0), Reg8::E, 0),
-            0xcb44 => self.arith_imm(AluOp::Test(0), Reg8::H, 0),
-            0xcb45 => self.arith_imm(AluOp::Test(0), Reg8::L, 0),
-            0xcb46 => self.bitwise_hl_ptr(AluOp::Test(0)),
-            0xcb47 => self.arith_imm(AluOp::Test(0), Reg8::A, 0),
-            0xcb48 => self.arith_imm(AluOp::Test(1), Reg8::B, 0),
-            0xcb49 => self.arith_imm(AluOp::Test(1), Reg8::C, 0),
-            0xcb4a => self.arith_imm(AluOp::Test(1), Reg8::D, 0),
-            0xcb4b => self.arith_imm(AluOp::Test(1), Reg8::E, 0),
-            0xcb4c => self.arith_imm(AluOp::Test(1), Reg8::H, 0),
-            0xcb4d => self.arith_imm(AluOp::Test(1), Reg8::L, 0),
-            0xcb4e => self.bitwise_hl_ptr(AluOp::Test(1)),
-            0xcb4f => self.arith_imm(AluOp::Test(1), Reg8::A, 0),
-            0xcb50 => self.arith_imm(AluOp::Test(2), Reg8::B, 0),
-            0xcb51 => self.arith_imm(AluOp::Test(2), Reg8::C, 0),
-            0xcb52 => self.arith_imm(AluOp::Test(2), Reg8::D, 0),
-            0xcb53 => self.arith_imm(AluOp::Test(2), Reg8::E, 0),
-            0xcb54 => self.arith_imm(AluOp::Test(2), Reg8::H, 0),
-            0xcb55 => self.arith_imm(AluOp::Test(2), Reg8::L, 0),
-            0xcb56 => self.bitwise_hl_ptr(AluOp::Test(2)),
-            0xcb57 => self.arith_imm(AluOp::Test(2), Reg8::A, 0),
-            0xcb58 => self.arith_imm(AluOp::Test(3), Reg8::B, 0),
-            0xcb59 => self.arith_imm(AluOp::Test(3), Reg8::C, 0),
-            0xcb5a => self.arith_imm(AluOp::Test(3), Reg8::D, 0),
-            0xcb5b => self.arith_imm(AluOp::Test(3), Reg8::E, 0),
-            0xcb5c => self.arith_imm(AluOp::Test(3), Reg8::H, 0),
-            0xcb5d => self.arith_imm(AluOp::Test(3), Reg8::L, 0),
-            0xcb5e => self.bitwise_hl_ptr(AluOp::Test(3)),
-            0xcb5f => self.arith_imm(AluOp::Test(3), Reg8::A, 0),
-            0xcb60 => self.arith_imm(AluOp::Test(4), Reg8::B, 0),
-            0xcb61 => self.arith_imm(AluOp::Test(4), Reg8::C, 0),
-            0xcb62 => self.arith_imm(AluOp::Test(4), Reg8::D, 0),
-            0xcb63 => self.arith_imm(AluOp::Test(4), Reg8::E, 0),
-            0xcb64 => self.arith_imm(AluOp::Test(4), Reg8::H, 0),
-            0xcb65 => self.arith_imm(AluOp::Test(4), Reg8::L, 0),
-            0xcb66 => self.bitwise_hl_ptr(AluOp::Test(4)),
-            0xcb67 => self.arith_imm(AluOp::Test(4), Reg8::A, 0),
-            0xcb68 => self.arith_imm(AluOp::Test(5), Reg8::B, 0),
-            0xcb69 => self.arith_imm(AluOp::Test(5), Reg8::C, 0),
-            0xcb6a => self.arith_imm(AluOp::Test(5), Reg8::D, 0),
-            0xcb6b => self.arith_imm(AluOp::Test(5), Reg8::E, 0),
-            0xcb6c => self.arith_imm(AluOp::Test(5), Reg8::H, 0),
-            0xcb6d => self.arith_imm(AluOp::Test(5), Reg8::L, 0),
-            0xcb6e => self.bitwise_hl_ptr(AluOp::Test(5)),
-            0xcb6f => self.arith_imm(AluOp::Test(5), Reg8::A, 0),
-            0xcb70 => self.arith_imm(AluOp::Test(6), Reg8::B, 0),
-            0xcb71 => self.arith_imm(AluOp::Test(6), Reg8::C, 0),
-            0xcb72 => self.arith_imm(AluOp::Test(6), Reg8::D, 0),
-            0xcb73 => self.arith_imm(AluOp::Test(6), Reg8::E, 0),
-            0xcb74 => self.arith_imm(AluOp::Test(6), Reg8::H, 0),
-            0xcb75 => self.arith_imm(AluOp::Test(6), Reg8::L, 0),
-            0xcb76 => self.bitwise_hl_ptr(AluOp::Test(6)),
-            0xcb77 => self.arith_imm(AluOp::Test(6), Reg8::A, 0),
-            0xcb78 => self.arith_imm(AluOp::Test(7), Reg8::B, 0),
-            0xcb79 => self.arith_imm(AluOp::Test(7), Reg8::C, 0),
-            0xcb7a => self.arith_imm(AluOp::Test(7), Reg8::D, 0),
-            0xcb7b => self.arith_imm(AluOp::Test(7), Reg8::E, 0),
-            0xcb7c => self.arith_imm(AluOp::Test(7), Reg8::H, 0),
-            0xcb7d => self.arith_imm(AluOp::Test(7), Reg8::L, 0),
-            0xcb7e => self.bitwise_hl_ptr(AluOp::Test(7)),
-            0xcb7f => self.arith_imm(AluOp::Test(7), Reg8::A, 0),
-
-            // [0xcb80, 0xcbb9] - Reset bit to 0
-            0xcb80 => self.arith_imm(AluOp::Set(0, false), Reg8::B, 0),
-            0xcb81 => self.arith_imm(AluOp::Set(0, false), Reg8::C, 0),
-            0xcb82 => self.arith_imm(AluOp::Set(0, false), Reg8::D, 0),
-            0xcb83 => self.arith_imm(AluOp::Set(0, false), Reg8::E, 0),
-            0xcb84 => self.arith_imm(AluOp::Set(0, false), Reg8::H, 0),
-            0xcb85 => self.arith_imm(AluOp::Set(0, false), Reg8::L, 0),
-            0xcb86 => self.bitwise_hl_ptr(AluOp::Set(0, false)),
-            0xcb87 => self.arith_imm(AluOp::Set(0, false), Reg8::A, 0),
-            0xcb88 => self.arith_imm(AluOp::Set(1, false), Reg8::B, 0),
-            0xcb89 => self.arith_imm(AluOp::Set(1, false), Reg8::C, 0),
-            0xcb8a => self.arith_imm(AluOp::Set(1, false), Reg8::D, 0),
-            0xcb8b => self.arith_imm(AluOp::Set(1, false), Reg8::E, 0),
-            0xcb8c => self.arith_imm(AluOp::Set(1, false), Reg8::H, 0),
-            0xcb8d => self.arith_imm(AluOp::Set(1, false), Reg8::L, 0),
-            0xcb8e => self.bitwise_hl_ptr(AluOp::Set(1, false)),
-            0xcb8f => self.arith_imm(AluOp::Set(1, false), Reg8::A, 0),
-            0xcb90 => self.arith_imm(AluOp::Set(2, false), Reg8::B, 0),
-            0xcb91 => self.arith_imm(AluOp::Set(2, false), Reg8::C, 0),
-            0xcb92 => self.arith_imm(AluOp::Set(2, false), Reg8::D, 0),
-            0xcb93 => self.arith_imm(AluOp::Set(2, false), Reg8::E, 0),
-            0xcb94 => self.arith_imm(AluOp::Set(2, false), Reg8::H, 0),
-            0xcb95 => self.arith_imm(AluOp::Set(2, false), Reg8::L, 0),
-            0xcb96 => self.bitwise_hl_ptr(AluOp::Set(2, false)),
-            0xcb97 => self.arith_imm(AluOp::Set(2, false), Reg8::A, 0),
-            0xcb98 => self.arith_imm(AluOp::Set(3, false), Reg8::B, 0),
-            0xcb99 => self.arith_imm(AluOp::Set(3, false), Reg8::C, 0),
-            0xcb9a => self.arith_imm(AluOp::Set(3, false), Reg8::D, 0),
-            0xcb9b => self.arith_imm(AluOp::Set(3, false), Reg8::E, 0),
-            0xcb9c => self.arith_imm(AluOp::Set(3, false), Reg8::H, 0),
-            0xcb9d => self.arith_imm(AluOp::Set(3, false), Reg8::L, 0),
-            0xcb9e => self.bitwise_hl_ptr(AluOp::Set(3, false)),
-            0xcb9f => self.arith_imm(AluOp::Set(3, false), Reg8::A, 0),
-            0xcba0 => self.arith_imm(AluOp::Set(4, false), Reg8::B, 0),
-            0xcba1 => self.arith_imm(AluOp::Set(4, false), Reg8::C, 0),
-            0xcba2 => self.arith_imm(AluOp::Set(4, false), Reg8::D, 0),
-            0xcba3 => self.arith_imm(AluOp::Set(4, false), Reg8::E, 0),
-            0xcba4 => self.arith_imm(AluOp::Set(4, false), Reg8::H, 0),
-            0xcba5 => self.arith_imm(AluOp::Set(4, false), Reg8::L, 0),
-            0xcba6 => self.bitwise_hl_ptr(AluOp::Set(4, false)),
-            0xcba7 => self.arith_imm(AluOp::Set(4, false), Reg8::A, 0),
-            0xcba8 => self.arith_imm(AluOp::Set(5, false), Reg8::B, 0),
-            0xcba9 => self.arith_imm(AluOp::Set(5, false), Reg8::C, 0),
-            0xcbaa => self.arith_imm(AluOp::Set(5, false), Reg8::D, 0),
-            0xcbab => self.arith_imm(AluOp::Set(5, false), Reg8::E, 0),
-            0xcbac => self.arith_imm(AluOp::Set(5, false), Reg8::H, 0),
-            0xcbad => self.arith_imm(AluOp::Set(5, false), Reg8::L, 0),
-            0xcbae => self.bitwise_hl_ptr(AluOp::Set(5, false)),
-            0xcbaf => self.arith_imm(AluOp::Set(5, false), Reg8::A, 0),
-            0xcbb0 => self.arith_imm(AluOp::Set(6, false), Reg8::B, 0),
-            0xcbb1 => self.arith_imm(AluOp::Set(6, false), Reg8::C, 0),
-            0xcbb2 => self.arith_imm(AluOp::Set(6, false), Reg8::D, 0),
-            0xcbb3 => self.arith_imm(AluOp::Set(6, false), Reg8::E, 0),
-            0xcbb4 => self.arith_imm(AluOp::Set(6, false), Reg8::H, 0),
-            0xcbb5 => self.arith_imm(AluOp::Set(6, false), Reg8::L, 0),
-            0xcbb6 => self.bitwise_hl_ptr(AluOp::Set(6, false)),
-            0xcbb7 => self.arith_imm(AluOp::Set(6, false), Reg8::A, 0),
-            0xcbb8 => self.arith_imm(AluOp::Set(7, false), Reg8::B, 0),
-            0xcbb9 => self.arith_imm(AluOp::Set(7, false), Reg8::C, 0),
-            0xcbba => self.arith_imm(AluOp::Set(7, false), Reg8::D, 0),
-            0xcbbb => self.arith_imm(AluOp::Set(7, false), Reg8::E, 0),
-            0xcbbc => self.arith_imm(AluOp::Set(7, false), Reg8::H, 0),
-            0xcbbd => self.arith_imm(AluOp::Set(7, false), Reg8::L, 0),
-            0xcbbe => self.bitwise_hl_ptr(AluOp::Set(7, false)),
-            0xcbbf => self.arith_imm(AluOp::Set(7, false), Reg8::A, 0),
-
-            // [0xcbc0, 0xcbf9] - Set bit to 1
-            0xcbc0 => self.arith_imm(AluOp::Set(0, true), Reg8::B, 0),
-            0xcbc1 => self.arith_imm(AluOp::Set(0, true), Reg8::C, 0),
-            0xcbc2 => self.arith_imm(AluOp::Set(0, true), Reg8::D, 0),
-            0xcbc3 => self.arith_imm(AluOp::Set(0, true), Reg8::E, 0),
-            0xcbc4 => self.arith_imm(AluOp::Set(0, true), Reg8::H, 0),
-            0xcbc5 => self.arith_imm(AluOp::Set(0, true), Reg8::L, 0),
-            0xcbc6 => self.bitwise_hl_ptr(AluOp::Set(0, true)),
-            0xcbc7 => self.arith_imm(AluOp::Set(0, true), Reg8::A, 0),
-            0xcbc8 => self.arith_imm(AluOp::Set(1, true), Reg8::B, 0),
-            0xcbc9 => self.arith_imm(AluOp::Set(1, true), Reg8::C, 0),
-            0xcbca => self.arith_imm(AluOp::Set(1, true), Reg8::D, 0),
-            0xcbcb => self.arith_imm(AluOp::Set(1, true), Reg8::E, 0),
-            0xcbcc => self.arith_imm(AluOp::Set(1, true), Reg8::H, 0),
-            0xcbcd => self.arith_imm(AluOp::Set(1, true), Reg8::L, 0),
-            0xcbce => self.bitwise_hl_ptr(AluOp::Set(1, true)),
-            0xcbcf => self.arith_imm(AluOp::Set(1, true), Reg8::A, 0),
-            0xcbd0 => self.arith_imm(AluOp::Set(2, true), Reg8::B, 0),
-            0xcbd1 => self.arith_imm(AluOp::Set(2, true), Reg8::C, 0),
-            0xcbd2 => self.arith_imm(AluOp::Set(2, true), Reg8::D, 0),
-            0xcbd3 => self.arith_imm(AluOp::Set(2, true), Reg8::E, 0),
-            0xcbd4 => self.arith_imm(AluOp::Set(2, true), Reg8::H, 0),
-            0xcbd5 => self.arith_imm(AluOp::Set(2, true), Reg8::L, 0),
-            0xcbd6 => self.bitwise_hl_ptr(AluOp::Set(2, true)),
-            0xcbd7 => self.arith_imm(AluOp::Set(2, true), Reg8::A, 0),
-            0xcbd8 => self.arith_imm(AluOp::Set(3, true), Reg8::B, 0),
-            0xcbd9 => self.arith_imm(AluOp::Set(3, true), Reg8::C, 0),
-            0xcbda => self.arith_imm(AluOp::Set(3, true), Reg8::D, 0),
-            0xcbdb => self.arith_imm(AluOp::Set(3, true), Reg8::E, 0),
-            0xcbdc => self.arith_imm(AluOp::Set(3, true), Reg8::H, 0),
-            0xcbdd => self.arith_imm(AluOp::Set(3, true), Reg8::L, 0),
-            0xcbde => self.bitwise_hl_ptr(AluOp::Set(3, true)),
-            0xcbdf => self.arith_imm(AluOp::Set(3, true), Reg8::A, 0),
-            0xcbe0 => self.arith_imm(AluOp::Set(4, true), Reg8::B, 0),
-            0xcbe1 => self.arith_imm(AluOp::Set(4, true), Reg8::C, 0),
-            0xcbe2 => self.arith_imm(AluOp::Set(4, true), Reg8::D, 0),
-            0xcbe3 => self.arith_imm(AluOp::Set(4, true), Reg8::E, 0),
-            0xcbe4 => self.arith_imm(AluOp::Set(4, true), Reg8::H, 0),
-            0xcbe5 => self.arith_imm(AluOp::Set(4, true), Reg8::L, 0),
-            0xcbe6 => self.bitwise_hl_ptr(AluOp::Set(4, true)),
-            0xcbe7 => self.arith_imm(AluOp::Set(4, true), Reg8::A, 0),
-            0xcbe8 => self.arith_imm(AluOp::Set(5, true), Reg8::B, 0),
-            0xcbe9 => self.arith_imm(AluOp::Set(5, true), Reg8::C, 0),
-            0xcbea => self.arith_imm(AluOp::Set(5, true), Reg8::D, 0),
-            0xcbeb => self.arith_imm(AluOp::Set(5, true), Reg8::E, 0),
-            0xcbec => self.arith_imm(AluOp::Set(5, true), Reg8::H, 0),
-            0xcbed => self.arith_imm(AluOp::Set(5, true), Reg8::L, 0),
-            0xcbee => self.bitwise_hl_ptr(AluOp::Set(5, true)),
-            0xcbef => self.arith_imm(AluOp::Set(5, true), Reg8::A, 0),
-            0xcbf0 => self.arith_imm(AluOp::Set(6, true), Reg8::B, 0),
-            0xcbf1 => self.arith_imm(AluOp::Set(6, true), Reg8::C, 0),
-            0xcbf2 => self.arith_imm(AluOp::Set(6, true), Reg8::D, 0),
-            0xcbf3 => self.arith_imm(AluOp::Set(6, true), Reg8::E, 0),
-            0xcbf4 => self.arith_imm(AluOp::Set(6, true), Reg8::H, 0),
-            0xcbf5 => self.arith_imm(AluOp::Set(6, true), Reg8::L, 0),
-            0xcbf6 => self.bitwise_hl_ptr(AluOp::Set(6, true)),
-            0xcbf7 => self.arith_imm(AluOp::Set(6, true), Reg8::A, 0),
-            0xcbf8 => self.arith_imm(AluOp::Set(7, true), Reg8::B, 0),
-            0xcbf9 => self.arith_imm(AluOp::Set(7, true), Reg8::C, 0),
-            0xcbfa => self.arith_imm(AluOp::Set(7, true), Reg8::D, 0),
-            0xcbfb => self.arith_imm(AluOp::Set(7, true), Reg8::E, 0),
-            0xcbfc => self.arith_imm(AluOp::Set(7, true), Reg8::H, 0),
-            0xcbfd => self.arith_imm(AluOp::Set(7, true), Reg8::L, 0),
-            0xcbfe => self.bitwise_hl_ptr(AluOp::Set(7, true)),
-            0xcbff => self.arith_imm(AluOp::Set(7, true), Reg8::A, 0),
-
-            _ => {
-                println!("Fatal error: undefined instruction! Opcode: 0x{:02x}", opcode);
-                self.regs.print_registers();
-                self.quit = true;
-            }
+        self.instructions += 1;
+        if self.quit { return self.mcycles * 4; }
+
+        // An EI from the previous instruction takes effect once this one has executed.
+        let ime_was_pending = self.ime == ImeState::Pending;
+        self.branch_taken = false;
+
+        self.inst_pc = old_pc;
+        match cached {
+            Some(decoded) => (decoded.handler)(self, decoded.op),
+            None => self.execute(opcode),
         }
+        // STOP skips its padding byte without fetching it, so don't leave it for the next fetch.
+        self.operands_left = 0;
 
         // After instruction, sync flag changes to register cache
         self.sync_flags();
 
-        !self.quit
+        if ime_was_pending && self.ime == ImeState::Pending {
+            self.ime = ImeState::Enabled;
+        }
+
+        // Every memory access and internal delay above ran on its own machine cycle, so the total
+        // should match the timing from the lookup table. Conditional jumps, calls and returns take
+        // longer when the branch is taken.
+        let expected_clocks = if self.branch_taken {
+            self.inst.clocks + self.inst.clocks_extra
+        } else {
+            self.inst.clocks
+        };
+        debug_assert!(self.quit || self.mcycles * 4 == expected_clocks as u32,
+                      "{} took {} clocks, expected {}", self.inst.name, self.mcycles * 4, expected_clocks);
+
+        self.mcycles * 4
+    }
+
+    // Fetch the opcode at pc, including the second byte of CB prefixed opcodes. With the HALT bug
+    // PC isn't incremented, so the next byte is read from the same address again.
+    fn fetch_opcode(&mut self, pc: u16) -> u16 {
+        let opcode = self.read_cycle(pc);
+        if !self.halt_bug {
+            self.regs.set(Reg16::PC, pc.wrapping_add(1));
+        }
+        self.halt_bug = false;
+
+        if opcode == 0xcb {
+            0xcb00 | self.fetch8() as u16
+        } else {
+            opcode as u16
+        }
+    }
+
+    // Take an instruction from the block cache instead of memory. The opcode and operand bytes are
+    // still fetched on the same cycles, they just aren't read again.
+    fn fetch_cached(&mut self, decoded: &Decoded) -> u16 {
+        let prefix_len = if decoded.opcode > 0xff { 2 } else { 1 };
+        for _ in 0..prefix_len { self.cycle(); }
+        self.regs.add(Reg16::PC, prefix_len);
+        self.operands = decoded.operands;
+        self.operands_left = decoded.inst.bytes - prefix_len as u8;
+        decoded.opcode
+    }
+
+    // Start writing a line to the trace for every instruction.
+    pub fn set_trace(&mut self, trace: TraceLog) {
+        self.trace = Some(trace);
+    }
+
+    pub fn flush_trace(&mut self) {
+        if let Some(Err(e)) = self.trace.as_mut().map(TraceLog::flush) {
+            eprintln!("Error writing trace: {}", e);
+        }
+    }
+
+    // Write the state at the start of the next instruction to the trace. Tracing stops if the
+    // write fails, rather than stopping the emulator.
+    fn write_trace(&mut self) {
+        let pc = self.regs.get(Reg16::PC);
+        let state = TraceState {
+            a: self.regs.get(Reg8::A),
+            f: self.regs.get(Reg8::F),
+            b: self.regs.get(Reg8::B),
+            c: self.regs.get(Reg8::C),
+            d: self.regs.get(Reg8::D),
+            e: self.regs.get(Reg8::E),
+            h: self.regs.get(Reg8::H),
+            l: self.regs.get(Reg8::L),
+            sp: self.regs.get(Reg16::SP),
+            pc,
+            pcmem: [0, 1, 2, 3].map(|i| self.mem_get(pc.wrapping_add(i))),
+            ly: Some(self.mem_get(PPUReg::Ly as u16)),
+            clocks: Some(self.clocks + self.system_clocks(self.mcycles)),
+        };
+        if let Some(Err(e)) = self.trace.as_mut().map(|trace| trace.write(state)) {
+            eprintln!("Error writing trace, stopping it: {}", e);
+            self.trace = None;
+        }
     }
 
     fn handle_debugging(&mut self, pc: u16) {
-        let mut should_break = false;
-        if self.breaks.contains(&pc) { should_break = true; }
+        let mut reason = None;
+        if self.breaks.contains(&pc) { reason = Some(BreakReason::Breakpoint); }
+        if self.break_at_instruction == Some(self.instructions) {
+            reason = Some(BreakReason::Instruction(self.instructions));
+        }
         if self.stepover_break == Some(pc) || self.stepinto {
-            should_break = true;
+            reason = reason.or(Some(BreakReason::Step));
             self.stepinto = false;
             self.stepover_break = None;
         }
 
-        if should_break {
-            self.print_instruction_info(pc, true);
-            self.get_breakpoint_input(pc);
+        if let Some(reason) = reason {
+            self.enter_debugger(pc, reason);
         } else if self.verbose {
             self.print_instruction_info(pc, false);
         }
@@ -1142,18 +956,25 @@ impl CPU {
         }
     }
 
+    // Report why we stopped, then wait for debugger commands.
+    fn enter_debugger(&mut self, pc: u16, reason: BreakReason) {
+        let mut stdout = StandardStream::stdout(ColorChoice::Always);
+        let color = match reason {
+            BreakReason::Lockup(_) => Color::Red,
+            _ => Color::Yellow,
+        };
+        stdout.set_color(ColorSpec::new().set_fg(Some(color)).set_bold(true)).unwrap();
+        writeln!(&mut stdout, "{} at 0x{:04x}", reason, pc).unwrap();
+        stdout.set_color(ColorSpec::new().set_fg(None)).unwrap();
+
+        self.print_instruction_info(pc, true);
+        self.get_breakpoint_input(pc);
+    }
+
     fn print_instruction_info(&self, cur_pc: u16, is_break: bool) {
-        let mut pstr = format!("0x{:04x}: {} - {} cycles", cur_pc, self.inst.name, self.inst.clocks);
-        if self.inst.bytes > 1 && !self.inst.prefix_cb {
-            pstr += " - operands: ";
-            if self.inst.bytes == 3 {
-                pstr += &format!("0x{:04x}", self.parse_u16(cur_pc + 1 as u16));
-            } else {
-                for i in 1..self.inst.bytes {
-                    pstr += &format!("0x{:02x} ", self.mem_get(cur_pc + i as u16));
-                }
-            }
-        }
+        let bytes: Vec<u8> = (0..self.inst.bytes as u16).map(|i| self.mem_get(cur_pc.wrapping_add(i))).collect();
+        let (text, _) = disasm::disassemble(&bytes, cur_pc);
+        let pstr = format!("0x{:04x}: {} - {} cycles", cur_pc, text, self.inst.clocks);
         let mut stdout = StandardStream::stdout(ColorChoice::Always);
         if is_break {
             stdout.set_color(ColorSpec::new().set_fg(Some(Color::Yellow)).set_bold(true)).unwrap();
@@ -1177,7 +998,7 @@ impl CPU {
     fn get_breakpoint_input(&mut self, cur_pc: u16) {
         let mut done = false;
         while !done {
-            print!("Press \'c\' to continue, \'s\' to step, \'p\' to print regs: ");
+            print!("Press \'c\' to continue, \'s\' to step, \'p\' to print regs, \'a [address] [instruction]\' to assemble: ");
             let mut selection = String::new();
             io::stdout().flush().ok().expect("Problem flushing stdout.");
             io::stdin().read_line(&mut selection).expect("Could not read from stdin!");
@@ -1199,10 +1020,60 @@ impl CPU {
                     let fname = format!("gblite_mem_{}_{:02}_{:02}_{}_runtime.log", dt.year(), dt.month(), dt.day(),
                                          dt.num_seconds_from_midnight());
                     let mref = self.mem.lock().unwrap(); mref.dump_to_file(fname.as_str()).unwrap(); }
+                cmd if cmd.starts_with("a ") => { self.assemble_command(&cmd[2..], cur_pc); }
                 _   => { done = true; }
             }
 
             self.last_break_arg = Some(selection);
         }
     }
+
+    // Assemble an instruction and write it straight into memory, including ROM, to patch the code.
+    fn assemble_command(&mut self, args: &str, cur_pc: u16) {
+        let (addr, text) = args.trim().split_once(' ').unwrap_or((args, ""));
+        let addr = match u16::from_str_radix(addr.trim_start_matches("0x").trim_start_matches('$'), 16) {
+            Ok(addr) => addr,
+            Err(_) => { println!("Usage: a [address] [instruction], with the address in hex"); return; }
+        };
+        let bytes = match asm::assemble(text, addr) {
+            Ok(bytes) => bytes,
+            Err(e) => { println!("Can't assemble: {}", e); return; }
+        };
+
+        // The current instruction's opcode has already been fetched. If it's being patched, hold on
+        // to its operands like cached code does, so it runs as it was and the patch applies next time.
+        let cur_end = cur_pc as u32 + self.inst.bytes as u32;
+        let patch_end = addr as u32 + bytes.len() as u32;
+        let mut operands = None;
+        if self.operands_left == 0 && (addr as u32) < cur_end && patch_end > cur_pc as u32 {
+            let prefix_len = if self.inst.prefix_cb { 2 } else { 1 };
+            let mut held = [0; 2];
+            for i in 0..(self.inst.bytes - prefix_len) {
+                held[i as usize] = self.mem_get(cur_pc.wrapping_add((prefix_len + i) as u16));
+            }
+            operands = Some((held, self.inst.bytes - prefix_len));
+        }
+
+        if let Err(e) = self.mem.lock().unwrap().patch(addr, &bytes) {
+            println!("Can't patch: {}", e);
+            return;
+        }
+        if let Some((held, count)) = operands {
+            self.operands = held;
+            self.operands_left = count;
+        }
+        // Patches don't go through write_cycle, so the block cache has to be told separately.
+        if let Some(blocks) = &mut self.blocks {
+            blocks.note_patch(addr, bytes.len() as u16);
+        }
+
+        let hex: Vec<String> = bytes.iter().map(|b| format!("{:02x}", b)).collect();
+        println!("0x{:04x}: {} ({})", addr, disasm::disassemble(&bytes, addr).0, hex.join(" "));
+    }
 }
+
+mod blocks;
+mod ops;
+
+#[cfg(test)]
+mod tests;
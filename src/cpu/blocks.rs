@@ -0,0 +1,222 @@
+// An optional cache of decoded basic blocks, runs of straight-line code that end at the first jump,
+// call, return, HALT or STOP. Instructions taken from the cache skip the opcode lookup and reading
+// their operands from memory, but still spend the same machine cycles doing so, so timing and the
+// rest of the system are unaffected. That also limits what the cache can save. The per-cycle work
+// for the rest of the system stays the same, so whole runs are only around 10-15% faster.
+//
+// Only code in ROM, WRAM and HRAM is cached. Blocks are dropped when something could change the code
+// under them: a write into their memory, or turning off the boot ROM. Code isn't cached during OAM
+// DMA, when the CPU can't read it normally. Blocks in the switchable ROM bank are kept separately
+// for each value written to the ROM bank register, so switching banks doesn't drop them, and
+// switching back finds them again.
+
+use std::collections::HashMap;
+
+use super::*;
+use super::ops::Handler;
+use crate::memory::{BOOT_ADDR, DMA_ADDR};
+
+// Blocks can't be longer than this, so a write only has to check the blocks starting just before it.
+const MAX_BLOCK_BYTES: u16 = 64;
+
+// Writes are checked against the code in memory at this granularity, and the I/O registers are one
+// line of their own, so writing to them doesn't invalidate code in HRAM.
+const LINE_SHIFT: u16 = 7;
+const LINES: usize = 0x10000 >> LINE_SHIFT;
+
+// The switchable ROM bank, and the cartridge register that selects it.
+const BANK_START: u16 = 0x4000;
+const BANK_END: u16 = 0x8000;
+const BANK_REGISTER: std::ops::RangeInclusive<u16> = 0x2000..=0x3FFF;
+
+// OAM DMA takes 160 machine cycles after a cycle of startup, cached code isn't used until it's done.
+const DMA_CLOCKS: u64 = 162 * 4;
+
+// One decoded instruction.
+#[derive(Copy, Clone)]
+pub struct Decoded {
+    pub pc: u16,
+    pub handler: Handler,
+    pub op: u8,
+    pub opcode: u16,
+    pub operands: [u8; 2],
+    pub inst: &'static Instruction,
+    pub flagmod: &'static FlagStatus,
+}
+
+struct Block {
+    start: u16,
+    end: u16, // One past the last byte of the block.
+    insts: Vec<Decoded>,
+}
+
+type Blocks = Vec<Option<Box<Block>>>;
+
+pub struct BlockCache {
+    blocks: Blocks,                  // Indexed by start address, for everything but the switchable bank.
+    banked: HashMap<u8, Blocks>,     // The switchable bank's blocks for each bank, from BANK_START.
+    bank: u8,                        // The value last written to the ROM bank register.
+    lines: Vec<bool>,                // Set for lines that might hold cached code.
+    current: Option<(u16, usize)>,   // The block being run, and the index of its next instruction.
+    bypass_until: u64,
+}
+
+// Whether a block may contain the byte at addr. Blocks stay within one of these areas.
+fn cacheable_area(addr: u16) -> Option<(u16, u16)> {
+    match addr {
+        0x0000..=0x3FFF => Some((0x0000, BANK_START)),
+        BANK_START..=0x7FFF => Some((BANK_START, BANK_END)),
+        0xC000..=0xDFFF => Some((0xC000, 0xE000)),
+        0xFF80..=0xFFFE => Some((0xFF80, 0xFFFF)),
+        _ => None,
+    }
+}
+
+// Instructions that can move PC anywhere other than the next instruction end a block.
+fn ends_block(opcode: u16) -> bool {
+    match opcode {
+        0x10 | 0x18 | 0x76 | 0xC3 | 0xC9 | 0xD9 | 0xE9 => true,
+        0x100..=0xFFFF => false,
+        op => op & 0xE7 == 0x20 || op & 0xE7 == 0xC0 || op & 0xE7 == 0xC2 || op & 0xE7 == 0xC4 ||
+              op & 0xC7 == 0xC7 || op == 0xCD,
+    }
+}
+
+impl BlockCache {
+    pub fn new() -> BlockCache {
+        BlockCache {
+            blocks: (0..0x10000).map(|_| None).collect(),
+            banked: HashMap::new(),
+            bank: 1,
+            lines: vec![false; LINES],
+            current: None,
+            bypass_until: 0,
+        }
+    }
+
+    // Find the decoded instruction at pc, decoding a new block from memory if needed. Returns None
+    // when the code at pc can't be cached right now.
+    pub fn lookup(&mut self, pc: u16, clocks: u64, mem: &Mutex<Memory>) -> Option<Decoded> {
+        if clocks < self.bypass_until { return None; }
+
+        // Carry on through the current block when we're at its next instruction.
+        if let Some((start, idx)) = self.current {
+            let resumes = self.slot(start).as_ref().is_some_and(|b| b.insts[idx].pc == pc);
+            if resumes {
+                return self.advance(start, idx);
+            }
+        }
+
+        if self.slot(pc).is_none() {
+            let block = decode_block(pc, &mem.lock().unwrap())?;
+            for line in (block.start >> LINE_SHIFT)..=((block.end - 1) >> LINE_SHIFT) {
+                self.lines[line as usize] = true;
+            }
+            *self.slot(pc) = Some(Box::new(block));
+        }
+        self.advance(pc, 0)
+    }
+
+    fn advance(&mut self, start: u16, idx: usize) -> Option<Decoded> {
+        let block = self.slot(start).as_ref()?;
+        let (decoded, len) = (block.insts[idx], block.insts.len());
+        self.current = if idx + 1 < len { Some((start, idx + 1)) } else { None };
+        Some(decoded)
+    }
+
+    // Where the block starting at start is kept, for the bank that's selected now.
+    fn slot(&mut self, start: u16) -> &mut Option<Box<Block>> {
+        if (BANK_START..BANK_END).contains(&start) {
+            let blocks = self.banked.entry(self.bank)
+                .or_insert_with(|| (BANK_START..BANK_END).map(|_| None).collect());
+            &mut blocks[(start - BANK_START) as usize]
+        } else {
+            &mut self.blocks[start as usize]
+        }
+    }
+
+    // Drop any cached code that the CPU writing val to addr could have changed. Writes to the rest
+    // of the cartridge registers, like enabling its RAM, leave the code alone.
+    pub fn note_write(&mut self, addr: u16, val: u8, clocks: u64) {
+        match addr {
+            _ if BANK_REGISTER.contains(&addr) => self.select_bank(val),
+            0x0000..=0x7FFF => (),
+            0xE000..=0xFDFF => self.invalidate(addr - 0x2000, addr - 0x1FFF), // Echo of WRAM.
+            BOOT_ADDR => self.invalidate(0x0000, 0x0100),
+            DMA_ADDR => self.bypass_until = clocks + DMA_CLOCKS,
+            _ if cacheable_area(addr).is_some() => self.invalidate(addr, addr + 1),
+            _ => (),
+        }
+    }
+
+    // Drop any cached code overlapping len bytes at addr, which were changed without a CPU write.
+    pub fn note_patch(&mut self, addr: u16, len: u16) {
+        for addr in (0..len).map(|i| addr.wrapping_add(i)).filter(|a| cacheable_area(*a).is_some()) {
+            self.invalidate(addr, addr + 1);
+        }
+    }
+
+    // Switch the blocks used for the switchable bank. The block being run is left if it's in there.
+    fn select_bank(&mut self, bank: u8) {
+        if bank == self.bank { return; }
+        self.bank = bank;
+        if self.current.is_some_and(|(start, _)| (BANK_START..BANK_END).contains(&start)) {
+            self.current = None;
+        }
+    }
+
+    // Drop every block that overlaps [from, to), in every bank.
+    fn invalidate(&mut self, from: u16, to: u16) {
+        let (first, last) = (from >> LINE_SHIFT, (to - 1) >> LINE_SHIFT);
+        if !self.lines[first as usize..=last as usize].iter().any(|l| *l) { return; }
+
+        for start in from.saturating_sub(MAX_BLOCK_BYTES)..to {
+            let mut dropped = false;
+            if (BANK_START..BANK_END).contains(&start) {
+                for blocks in self.banked.values_mut() {
+                    dropped |= drop_overlapping(&mut blocks[(start - BANK_START) as usize], from, to);
+                }
+            } else {
+                dropped = drop_overlapping(&mut self.blocks[start as usize], from, to);
+            }
+            if dropped && self.current.is_some_and(|(s, _)| s == start) { self.current = None; }
+        }
+    }
+}
+
+// Drop the block in slot if it overlaps [from, to), and return whether it did.
+fn drop_overlapping(slot: &mut Option<Box<Block>>, from: u16, to: u16) -> bool {
+    let overlaps = slot.as_ref().is_some_and(|block| block.end > from && block.start < to);
+    if overlaps { *slot = None; }
+    overlaps
+}
+
+// Decode instructions from pc up to the end of the basic block. Illegal opcodes and instructions
+// that would cross out of the cacheable area aren't included, and are left to the normal fetch.
+fn decode_block(pc: u16, mem: &Memory) -> Option<Block> {
+    let (_, area_end) = cacheable_area(pc)?;
+    let limit = area_end.min(pc.saturating_add(MAX_BLOCK_BYTES)) as u32;
+    let mut insts = Vec::new();
+    let mut addr = pc as u32;
+
+    loop {
+        let byte = mem.get(addr as u16, MemClient::CPU);
+        let opcode = if byte == 0xCB { 0xCB00 | mem.get((addr + 1) as u16, MemClient::CPU) as u16 } else { byte as u16 };
+        let inst = lookup::get_instruction(opcode);
+        let (handler, op) = ops::handler(opcode);
+        if ops::is_illegal(opcode) || addr + inst.bytes as u32 > limit { break; }
+
+        let prefix = if opcode > 0xFF { 2 } else { 1 };
+        let mut operands = [0; 2];
+        for (i, operand) in operands.iter_mut().enumerate().take((inst.bytes - prefix) as usize) {
+            *operand = mem.get((addr + prefix as u32 + i as u32) as u16, MemClient::CPU);
+        }
+
+        insts.push(Decoded { pc: addr as u16, handler, op, opcode, operands, inst, flagmod: lookup::get_flagmod(opcode) });
+        addr += inst.bytes as u32;
+        if ends_block(opcode) { break; }
+    }
+
+    if insts.is_empty() { return None; }
+    Some(Block { start: pc, end: addr as u16, insts })
+}
@@ -0,0 +1,453 @@
+// Instruction handlers, and the table that dispatches the base opcodes to them. Opcodes that share a
+// layout share a handler, which decodes its operands from the opcode bits:
+//
+//   r8:  bits 0-2 or 3-5 select B, C, D, E, H, L, (HL) or A.
+//   r16: bits 4-5 select BC, DE, HL or SP. PUSH and POP use AF in place of SP.
+//   cc:  bits 3-4 select the NZ, Z, NC or C condition.
+//   u3:  bits 3-5 are the bit number for the CB prefixed BIT, RES and SET.
+//
+// The CB prefixed opcodes are regular enough that execute_cb() handles all of them. Operands are
+// fetched by the handler that needs them, which advances PC past them.
+
+use super::*;
+
+pub(super) type Handler = fn(&mut CPU, u8);
+
+static HANDLERS: [Handler; 256] = build_handlers();
+
+// The ALU operations selected by bits 3-5 of ADD/ADC/SUB/SBC/AND/XOR/OR/CP.
+const ALU_OPS: [AluOp; 8] = [
+    AluOp::Add(false),
+    AluOp::Add(true),
+    AluOp::Sub(false),
+    AluOp::Sub(true),
+    AluOp::And,
+    AluOp::Xor,
+    AluOp::Or,
+    AluOp::Comp,
+];
+
+// The rotates and shifts selected by bits 3-5 of the first quarter of CB opcodes. The first four
+// are also the rotates of A, RLCA/RRCA/RLA/RRA.
+const SHIFT_OPS: [AluOp; 8] = [
+    AluOp::RotateLeft(true),
+    AluOp::RotateRight(true),
+    AluOp::RotateLeft(false),
+    AluOp::RotateRight(false),
+    AluOp::ShiftLeft,
+    AluOp::ShiftRight(true),
+    AluOp::Swap,
+    AluOp::ShiftRight(false),
+];
+
+const fn build_handlers() -> [Handler; 256] {
+    let mut table = [CPU::op_illegal as Handler; 256];
+    let mut op = 0;
+    while op < 256 {
+        table[op] = decode(op as u8);
+        op += 1;
+    }
+    table
+}
+
+// Pick the handler for a base opcode. One-off opcodes are matched first, then the opcode groups.
+const fn decode(op: u8) -> Handler {
+    match op {
+        0x00 => CPU::op_nop,
+        0x08 => CPU::op_ld_a16_sp,
+        0x10 => CPU::op_stop,
+        0x18 => CPU::op_jr,
+        0x27 => CPU::op_daa,
+        0x2f => CPU::op_cpl,
+        0x37 => CPU::op_scf,
+        0x3f => CPU::op_ccf,
+        0x40 => CPU::op_ld_b_b,
+        0x76 => CPU::op_halt,
+        0xc3 => CPU::op_jp,
+        0xc9 => CPU::op_ret,
+        0xcb => CPU::op_prefix_cb,
+        0xcd => CPU::op_call,
+        0xd9 => CPU::op_reti,
+        0xe0 => CPU::op_ldh_a8_a,
+        0xe2 => CPU::op_ldh_c_a,
+        0xe8 => CPU::op_add_sp_e8,
+        0xe9 => CPU::op_jp_hl,
+        0xea => CPU::op_ld_a16_a,
+        0xf0 => CPU::op_ldh_a_a8,
+        0xf2 => CPU::op_ldh_a_c,
+        0xf3 => CPU::op_di,
+        0xf8 => CPU::op_ld_hl_sp_e8,
+        0xf9 => CPU::op_ld_sp_hl,
+        0xfa => CPU::op_ld_a_a16,
+        0xfb => CPU::op_ei,
+        _ if is_illegal(op as u16) => CPU::op_illegal,
+        _ if op & 0xc0 == 0x40 => CPU::op_ld_r8_r8,
+        _ if op & 0xc0 == 0x80 => CPU::op_alu_r8,
+        _ if op & 0xcf == 0x01 => CPU::op_ld_r16_d16,
+        _ if op & 0xcf == 0x02 => CPU::op_ld_mem_a,
+        _ if op & 0xcf == 0x03 => CPU::op_inc_r16,
+        _ if op & 0xcf == 0x09 => CPU::op_add_hl_r16,
+        _ if op & 0xcf == 0x0a => CPU::op_ld_a_mem,
+        _ if op & 0xcf == 0x0b => CPU::op_dec_r16,
+        _ if op & 0xc7 == 0x04 => CPU::op_inc_r8,
+        _ if op & 0xc7 == 0x05 => CPU::op_dec_r8,
+        _ if op & 0xc7 == 0x06 => CPU::op_ld_r8_d8,
+        _ if op & 0xe7 == 0x07 => CPU::op_rotate_a,
+        _ if op & 0xe7 == 0x20 => CPU::op_jr_cc,
+        _ if op & 0xe7 == 0xc0 => CPU::op_ret_cc,
+        _ if op & 0xcf == 0xc1 => CPU::op_pop,
+        _ if op & 0xe7 == 0xc2 => CPU::op_jp_cc,
+        _ if op & 0xe7 == 0xc4 => CPU::op_call_cc,
+        _ if op & 0xcf == 0xc5 => CPU::op_push,
+        _ if op & 0xc7 == 0xc6 => CPU::op_alu_d8,
+        _ if op & 0xc7 == 0xc7 => CPU::op_rst,
+        _ => CPU::op_illegal,
+    }
+}
+
+// The base opcodes that don't exist, and lock up the CPU.
+pub(super) const fn is_illegal(opcode: u16) -> bool {
+    matches!(opcode, 0xd3 | 0xdb | 0xdd | 0xe3 | 0xe4 | 0xeb | 0xec | 0xed | 0xf4 | 0xfc | 0xfd)
+}
+
+// The handler for an opcode, including any CB prefix, and the byte to pass it.
+pub(super) fn handler(opcode: u16) -> (Handler, u8) {
+    if opcode > 0xff {
+        (CPU::execute_cb, opcode as u8)
+    } else {
+        (HANDLERS[opcode as usize], opcode as u8)
+    }
+}
+
+fn reg8(idx: u8) -> Reg8 {
+    match idx & 0x7 {
+        0 => Reg8::B,
+        1 => Reg8::C,
+        2 => Reg8::D,
+        3 => Reg8::E,
+        4 => Reg8::H,
+        5 => Reg8::L,
+        _ => Reg8::A,
+    }
+}
+
+fn reg16(op: u8) -> Reg16 {
+    match (op >> 4) & 0x3 {
+        0 => Reg16::BC,
+        1 => Reg16::DE,
+        2 => Reg16::HL,
+        _ => Reg16::SP,
+    }
+}
+
+fn reg16_stack(op: u8) -> Reg16 {
+    match (op >> 4) & 0x3 {
+        0 => Reg16::BC,
+        1 => Reg16::DE,
+        2 => Reg16::HL,
+        _ => Reg16::AF,
+    }
+}
+
+impl CPU {
+    // Execute an instruction whose opcode, including any CB prefix, has already been fetched.
+    pub(super) fn execute(&mut self, opcode: u16) {
+        let (handler, op) = handler(opcode);
+        handler(self, op);
+    }
+
+    // Every CB prefixed instruction applies one ALU operation to an r8 operand. All but BIT write
+    // the result back.
+    fn execute_cb(&mut self, op: u8) {
+        let bit = (op >> 3) & 0x7;
+        let alu_op = match op >> 6 {
+            0 => SHIFT_OPS[bit as usize],
+            1 => AluOp::Test(bit),
+            2 => AluOp::Set(bit, false),
+            _ => AluOp::Set(bit, true),
+        };
+
+        let val = self.read_r8(op);
+        let result = self.alu(alu_op, val, 0);
+        if let AluOp::Test(_) = alu_op { return; }
+        self.write_r8(op, result);
+    }
+
+    // Read an r8 operand from the low three bits of idx. (HL) takes a machine cycle to read.
+    fn read_r8(&mut self, idx: u8) -> u8 {
+        if idx & 0x7 == 6 {
+            let addr = self.regs.get(Reg16::HL);
+            self.read_cycle(addr)
+        } else {
+            self.regs.get(reg8(idx))
+        }
+    }
+
+    // Write an r8 operand selected by the low three bits of idx.
+    fn write_r8(&mut self, idx: u8, val: u8) {
+        if idx & 0x7 == 6 {
+            let addr = self.regs.get(Reg16::HL);
+            self.write_cycle(val, addr);
+        } else {
+            self.regs.set(reg8(idx), val);
+        }
+    }
+
+    // The address for LD (r16),A and LD A,(r16). The third and fourth forms are (HL+) and (HL-),
+    // which move HL along after using it.
+    fn reg16_mem(&mut self, op: u8) -> u16 {
+        match (op >> 4) & 0x3 {
+            0 => self.regs.get(Reg16::BC),
+            1 => self.regs.get(Reg16::DE),
+            2 => { let hl = self.regs.get(Reg16::HL); self.regs.add(Reg16::HL, 1); hl },
+            _ => { let hl = self.regs.get(Reg16::HL); self.regs.sub(Reg16::HL, 1); hl },
+        }
+    }
+
+    // Evaluate the cc condition in bits 3-4 of the opcode.
+    fn condition(&self, op: u8) -> bool {
+        match (op >> 3) & 0x3 {
+            0 => !self.flag_z,
+            1 => self.flag_z,
+            2 => !self.flag_cy,
+            _ => self.flag_cy,
+        }
+    }
+
+    // Apply an ALU operation to A and the given operand, storing the result in A.
+    fn alu_a(&mut self, op: AluOp, val: u8) {
+        let a = self.regs.get(Reg8::A);
+        let result = self.alu(op, a, val);
+        self.regs.set(Reg8::A, result);
+    }
+
+    fn op_nop(&mut self, _op: u8) {}
+
+    fn op_ld_r16_d16(&mut self, op: u8) {
+        let val = self.fetch16();
+        self.regs.set(reg16(op), val);
+    }
+
+    fn op_ld_mem_a(&mut self, op: u8) {
+        let addr = self.reg16_mem(op);
+        let val = self.regs.get(Reg8::A);
+        self.write_cycle(val, addr);
+    }
+
+    fn op_ld_a_mem(&mut self, op: u8) {
+        let addr = self.reg16_mem(op);
+        let val = self.read_cycle(addr);
+        self.regs.set(Reg8::A, val);
+    }
+
+    fn op_inc_r16(&mut self, op: u8) {
+        self.inc_dec_u16(reg16(op), true);
+    }
+
+    fn op_dec_r16(&mut self, op: u8) {
+        self.inc_dec_u16(reg16(op), false);
+    }
+
+    fn op_add_hl_r16(&mut self, op: u8) {
+        self.add_hl(reg16(op));
+    }
+
+    fn op_inc_r8(&mut self, op: u8) {
+        let val = self.read_r8(op >> 3);
+        let result = self.alu(AluOp::Add(false), val, 1);
+        self.write_r8(op >> 3, result);
+    }
+
+    fn op_dec_r8(&mut self, op: u8) {
+        let val = self.read_r8(op >> 3);
+        let result = self.alu(AluOp::Sub(false), val, 1);
+        self.write_r8(op >> 3, result);
+    }
+
+    fn op_ld_r8_d8(&mut self, op: u8) {
+        let val = self.fetch8();
+        self.write_r8(op >> 3, val);
+    }
+
+    // RLCA, RRCA, RLA and RRA. Unlike the CB prefixed rotates, these always clear Z.
+    fn op_rotate_a(&mut self, op: u8) {
+        let a = self.regs.get(Reg8::A);
+        let result = self.alu(SHIFT_OPS[((op >> 3) & 0x3) as usize], a, 0);
+        self.regs.set(Reg8::A, result);
+    }
+
+    fn op_ld_a16_sp(&mut self, _op: u8) {
+        let addr = self.fetch16();
+        self.write_sp_to_ptr(addr);
+    }
+
+    fn op_stop(&mut self, _op: u8) {
+        self.stop();
+    }
+
+    fn op_jr(&mut self, _op: u8) {
+        let offset = self.fetch8();
+        self.jump_relative(offset);
+    }
+
+    fn op_jr_cc(&mut self, op: u8) {
+        let offset = self.fetch8();
+        if self.condition(op) {
+            self.branch_taken = true;
+            self.jump_relative(offset);
+        }
+    }
+
+    fn op_daa(&mut self, _op: u8) {
+        self.decimal_adjust();
+    }
+
+    fn op_cpl(&mut self, _op: u8) {
+        let a = self.regs.get(Reg8::A);
+        self.regs.set(Reg8::A, !a);
+    }
+
+    // SCF only sets flags, which is handled by the following call to sync_flags.
+    fn op_scf(&mut self, _op: u8) {}
+
+    fn op_ccf(&mut self, _op: u8) {
+        self.toggle_cy();
+    }
+
+    fn op_ld_b_b(&mut self, _op: u8) {
+        self.magic_breakpoint();
+    }
+
+    fn op_halt(&mut self, _op: u8) {
+        self.halt();
+    }
+
+    fn op_ld_r8_r8(&mut self, op: u8) {
+        let val = self.read_r8(op);
+        self.write_r8(op >> 3, val);
+    }
+
+    fn op_alu_r8(&mut self, op: u8) {
+        let val = self.read_r8(op);
+        self.alu_a(ALU_OPS[((op >> 3) & 0x7) as usize], val);
+    }
+
+    fn op_alu_d8(&mut self, op: u8) {
+        let val = self.fetch8();
+        self.alu_a(ALU_OPS[((op >> 3) & 0x7) as usize], val);
+    }
+
+    // Checking the condition takes a cycle, even when the return isn't taken.
+    fn op_ret_cc(&mut self, op: u8) {
+        self.cycle();
+        if self.condition(op) {
+            self.branch_taken = true;
+            self.ret(false);
+        }
+    }
+
+    fn op_ret(&mut self, _op: u8) {
+        self.ret(false);
+    }
+
+    fn op_reti(&mut self, _op: u8) {
+        self.ret(true);
+    }
+
+    fn op_pop(&mut self, op: u8) {
+        self.pop(reg16_stack(op));
+    }
+
+    fn op_push(&mut self, op: u8) {
+        self.push(reg16_stack(op));
+    }
+
+    fn op_jp(&mut self, _op: u8) {
+        let addr = self.fetch16();
+        self.jump(addr);
+    }
+
+    fn op_jp_cc(&mut self, op: u8) {
+        let addr = self.fetch16();
+        if self.condition(op) {
+            self.branch_taken = true;
+            self.jump(addr);
+        }
+    }
+
+    fn op_jp_hl(&mut self, _op: u8) {
+        let addr = self.regs.get(Reg16::HL);
+        self.regs.set(Reg16::PC, addr);
+    }
+
+    fn op_call(&mut self, _op: u8) {
+        let addr = self.fetch16();
+        self.call(addr);
+    }
+
+    fn op_call_cc(&mut self, op: u8) {
+        let addr = self.fetch16();
+        if self.condition(op) {
+            self.branch_taken = true;
+            self.call(addr);
+        }
+    }
+
+    fn op_rst(&mut self, op: u8) {
+        self.call((op & 0x38) as u16);
+    }
+
+    // process() fetches the second byte of CB prefixed opcodes itself, so this is never dispatched.
+    fn op_prefix_cb(&mut self, _op: u8) {}
+
+    fn op_ldh_a8_a(&mut self, _op: u8) {
+        self.ldh_imm(false);
+    }
+
+    fn op_ldh_a_a8(&mut self, _op: u8) {
+        self.ldh_imm(true);
+    }
+
+    fn op_ldh_c_a(&mut self, _op: u8) {
+        self.ld_fast_page(false);
+    }
+
+    fn op_ldh_a_c(&mut self, _op: u8) {
+        self.ld_fast_page(true);
+    }
+
+    fn op_ld_a16_a(&mut self, _op: u8) {
+        self.ld_abs(false);
+    }
+
+    fn op_ld_a_a16(&mut self, _op: u8) {
+        self.ld_abs(true);
+    }
+
+    fn op_add_sp_e8(&mut self, _op: u8) {
+        let offset = self.fetch8();
+        self.add_sp_signed(Reg16::SP, offset as i8);
+    }
+
+    fn op_ld_hl_sp_e8(&mut self, _op: u8) {
+        let offset = self.fetch8();
+        self.add_sp_signed(Reg16::HL, offset as i8);
+    }
+
+    fn op_ld_sp_hl(&mut self, _op: u8) {
+        self.cycle();
+        self.regs.copy(Reg16::SP, Reg16::HL);
+    }
+
+    fn op_di(&mut self, _op: u8) {
+        self.disable_interrupts();
+    }
+
+    fn op_ei(&mut self, _op: u8) {
+        self.enable_interrupts();
+    }
+
+    fn op_illegal(&mut self, op: u8) {
+        self.lock_up(self.inst_pc, op);
+    }
+}
@@ -0,0 +1,579 @@
+// Single instruction conformance tests, using the SingleStepTests sm83 JSON vectors. Each vector
+// gives the CPU state and RAM before and after one instruction, and what happened on the bus during
+// each machine cycle. The CPU runs against a flat 64K test bus so nothing else gets in the way.
+//
+// The full suite isn't checked in, so its test is ignored by default. To run it, clone
+// https://github.com/SingleStepTests/sm83 into tests/sm83, or point the SM83_TESTS environment
+// variable at its v1 directory, and run `cargo test -- --ignored`. It fails if the suite isn't
+// there. A handful of vectors for bugs we've already fixed live in sm83_regressions.json, and
+// always run.
+
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+use serde_json::Value;
+
+use super::*;
+use crate::joypad::Button;
+use crate::memory::BusAccess;
+use crate::movie::FrameInput;
+use crate::script::Script;
+
+// Stop reporting failures for an opcode after this many, one opcode can easily fail them all.
+const MAX_REPORTED: usize = 5;
+
+fn test_cpu() -> CPU {
+    test_cpu_with(&RuntimeConfig::new())
+}
+
+fn test_cpu_with(cfg: &RuntimeConfig) -> CPU {
+    let mem = Arc::new(Mutex::new(Memory::new_flat()));
+    let ppu = PPU::headless(mem.clone());
+    CPU::new(mem, ppu, cfg)
+}
+
+// A CPU on the flat test bus, about to run code from the start of WRAM.
+fn code_cpu(code: &[u8]) -> CPU {
+    let mut cpu = test_cpu();
+    for (i, byte) in code.iter().enumerate() {
+        cpu.mem_set(*byte, 0xc000 + i as u16);
+    }
+    cpu.regs.set(Reg16::PC, 0xc000);
+    cpu
+}
+
+// A CPU on the full system bus, about to run code from the entry point of an otherwise empty ROM.
+fn rom_cpu(code: &[u8], cfg: &RuntimeConfig) -> CPU {
+    let mut rom = vec![0; 0x8000];
+    rom[0x100..0x100 + code.len()].copy_from_slice(code);
+    let mut mem = Memory::new(0x10000);
+    mem.load_rom(rom);
+    let mem = Arc::new(Mutex::new(mem));
+    let ppu = PPU::headless(mem.clone());
+    CPU::new(mem, ppu, cfg)
+}
+
+fn set_interrupts(cpu: &mut CPU, ie: u8, iflag: u8) {
+    cpu.mem_set(ie, interrupt::IE_ADDR);
+    cpu.mem_set(iflag, interrupt::IF_ADDR);
+}
+
+// The return address on top of the stack.
+fn stacked_pc(cpu: &CPU) -> u16 {
+    cpu.parse_u16(cpu.regs.get(Reg16::SP))
+}
+
+fn field(state: &Value, name: &str) -> u16 {
+    state[name].as_u64().unwrap_or_else(|| panic!("Missing field \"{}\"", name)) as u16
+}
+
+fn ram(state: &Value) -> Vec<(u16, u8)> {
+    state["ram"].as_array().expect("Missing field \"ram\"").iter()
+        .map(|e| (e[0].as_u64().unwrap() as u16, e[1].as_u64().unwrap() as u8))
+        .collect()
+}
+
+// The reads and writes we expect, from the per-cycle bus activity. Cycles with neither are internal.
+fn expected_accesses(cycles: &[Value]) -> Vec<BusAccess> {
+    cycles.iter().filter_map(|c| {
+        let pins = c[2].as_str().unwrap_or("---").as_bytes();
+        let write = pins.get(1) == Some(&b'w');
+        if pins.first() != Some(&b'r') && !write { return None; }
+        Some(BusAccess {
+            addr: c[0].as_u64().unwrap() as u16,
+            val: c[1].as_u64().unwrap() as u8,
+            write,
+        })
+    }).collect()
+}
+
+// Put the CPU into the initial state from the vector. The CPU is reused between vectors, so the
+// low power and interrupt states are cleared as well.
+fn load_state(cpu: &mut CPU, state: &Value) {
+    let regs8 = [("a", Reg8::A), ("b", Reg8::B), ("c", Reg8::C), ("d", Reg8::D),
+                 ("e", Reg8::E), ("f", Reg8::F), ("h", Reg8::H), ("l", Reg8::L)];
+    for (name, reg) in regs8.iter() {
+        cpu.regs.set(*reg, field(state, name) as u8);
+    }
+    cpu.regs.set(Reg16::PC, field(state, "pc"));
+    cpu.regs.set(Reg16::SP, field(state, "sp"));
+    cpu.load_flags();
+
+    cpu.ime = if field(state, "ime") != 0 { ImeState::Enabled } else { ImeState::Disabled };
+    cpu.halted = false;
+    cpu.halt_bug = false;
+    cpu.stopped = false;
+    cpu.locked = false;
+
+    if let Some(ie) = state["ie"].as_u64() {
+        cpu.mem_set(ie as u8, interrupt::IE_ADDR);
+    }
+    for (addr, val) in ram(state) {
+        cpu.mem_set(val, addr);
+    }
+    cpu.mem.lock().unwrap().take_bus_log();
+}
+
+// Compare the CPU against the final state from the vector, returning a description of every
+// difference.
+fn check_state(cpu: &mut CPU, state: &Value, cycles: &[Value], mcycles: u32) -> Vec<String> {
+    let mut errs = Vec::new();
+
+    let regs8 = [("a", Reg8::A), ("b", Reg8::B), ("c", Reg8::C), ("d", Reg8::D),
+                 ("e", Reg8::E), ("f", Reg8::F), ("h", Reg8::H), ("l", Reg8::L)];
+    for (name, reg) in regs8.iter() {
+        let (want, got) = (field(state, name), cpu.regs.get(*reg) as u16);
+        if want != got { errs.push(format!("{}: expected 0x{:02x}, got 0x{:02x}", name, want, got)); }
+    }
+    for (name, reg) in [("pc", Reg16::PC), ("sp", Reg16::SP)].iter() {
+        let (want, got) = (field(state, name), cpu.regs.get(*reg));
+        if want != got { errs.push(format!("{}: expected 0x{:04x}, got 0x{:04x}", name, want, got)); }
+    }
+
+    // The vectors don't model the EI delay, so a pending IME counts as set.
+    let ime = if cpu.ime == ImeState::Disabled { 0 } else { 1 };
+    if field(state, "ime") != ime {
+        errs.push(format!("ime: expected {}, got {}", field(state, "ime"), ime));
+    }
+
+    for (addr, want) in ram(state) {
+        let got = cpu.mem_get(addr);
+        if want != got { errs.push(format!("(0x{:04x}): expected 0x{:02x}, got 0x{:02x}", addr, want, got)); }
+    }
+
+    if mcycles as usize != cycles.len() {
+        errs.push(format!("took {} machine cycles, expected {}", mcycles, cycles.len()));
+    }
+
+    let want = expected_accesses(cycles);
+    let got = cpu.mem.lock().unwrap().take_bus_log();
+    if want != got {
+        errs.push(format!("bus accesses: expected {:?}, got {:?}", want, got));
+    }
+
+    errs
+}
+
+// Run every vector in the array, and return the failures as printable lines.
+fn run_vectors(cpu: &mut CPU, vectors: &Value) -> Vec<String> {
+    let mut failures = Vec::new();
+    for v in vectors.as_array().expect("Test file should hold an array of vectors") {
+        load_state(cpu, &v["initial"]);
+        let mcycles = cpu.process() / 4;
+
+        // Zero out the RAM used by this vector before the next one runs.
+        let errs = check_state(cpu, &v["final"], v["cycles"].as_array().unwrap(), mcycles);
+        for (addr, _) in ram(&v["initial"]).into_iter().chain(ram(&v["final"])) {
+            cpu.mem_set(0, addr);
+        }
+
+        if !errs.is_empty() {
+            failures.push(format!("{}: {}", v["name"].as_str().unwrap_or("?"), errs.join(", ")));
+        }
+    }
+
+    failures
+}
+
+// Every opcode except the illegal ones, with the CB prefix on the upper byte.
+fn all_opcodes() -> Vec<u16> {
+    let illegal = [0xcb, 0xd3, 0xdb, 0xdd, 0xe3, 0xe4, 0xeb, 0xec, 0xed, 0xf4, 0xfc, 0xfd];
+    (0x00..=0xff).filter(|op| !illegal.contains(op)).chain(0xcb00..=0xcbff).collect()
+}
+
+fn suite_dir() -> PathBuf {
+    match std::env::var_os("SM83_TESTS") {
+        Some(dir) => PathBuf::from(dir),
+        None => PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/sm83/v1"),
+    }
+}
+
+#[test]
+fn sm83_regressions() {
+    let vectors: Value = serde_json::from_str(include_str!("sm83_regressions.json")).unwrap();
+    let failures = run_vectors(&mut test_cpu(), &vectors);
+    assert!(failures.is_empty(), "\n{}", failures.join("\n"));
+}
+
+// Each model starts with its own register values, which is how games tell them apart. The test
+// bus has no cartridge, so the header checksum is 0 and the DMG leaves H and C clear.
+#[test]
+fn power_on_registers_match_the_model() {
+    let expected = [
+        (Model::Dmg0, [0x0100, 0xFF13, 0x00C1, 0x8403]),
+        (Model::Dmg,  [0x0180, 0x0013, 0x00D8, 0x014D]),
+        (Model::Sgb2, [0xFF00, 0x0014, 0x0000, 0xC060]),
+        (Model::Cgb,  [0x1180, 0x0000, 0x0008, 0x007C]),
+        (Model::Agb,  [0x1100, 0x0100, 0x0008, 0x007C]),
+    ];
+
+    for (model, regs) in expected.iter() {
+        let mut cfg = RuntimeConfig::new();
+        cfg.model = Some(*model);
+        let cpu = test_cpu_with(&cfg);
+        let got = [Reg16::AF, Reg16::BC, Reg16::DE, Reg16::HL].map(|r| cpu.regs.get(r));
+        assert_eq!(&got, regs, "{} registers", model);
+        assert_eq!((cpu.regs.get(Reg16::SP), cpu.regs.get(Reg16::PC)), (0xFFFE, 0x0100));
+        assert_eq!(cpu.mem_get(0xFF40), 0x91, "{} LCDC", model);
+    }
+}
+
+// A loop in WRAM that rewrites the operand of its own LD A,d8 each time around. Unless the block
+// cache drops the stale block after each write, A gets stuck at 1.
+#[test]
+fn block_cache_sees_self_modifying_code() {
+    let mut cfg = RuntimeConfig::new();
+    cfg.block_cache = true;
+    let mut cpu = test_cpu_with(&cfg);
+
+    // LD A,0; INC A; LD (0xC001),A; JP 0xC000
+    let code = [0x3e, 0x00, 0x3c, 0xea, 0x01, 0xc0, 0xc3, 0x00, 0xc0];
+    for (i, byte) in code.iter().enumerate() {
+        cpu.mem_set(*byte, 0xc000 + i as u16);
+    }
+    cpu.regs.set(Reg16::PC, 0xc000);
+
+    for _ in 0..10 * 4 {
+        cpu.process();
+    }
+    assert_eq!(cpu.regs.get(Reg8::A), 10);
+}
+
+// DI straight after EI cancels it before IME is ever set.
+#[test]
+fn ei_then_di_takes_no_interrupt() {
+    let mut cpu = code_cpu(&[0xfb, 0xf3, 0x00, 0x00]); // EI; DI; NOP; NOP
+    set_interrupts(&mut cpu, 0x01, 0x01);
+    for _ in 0..4 {
+        cpu.process();
+    }
+    assert_eq!(cpu.regs.get(Reg16::PC), 0xc004);
+    assert_eq!(cpu.mem_get(interrupt::IF_ADDR), 0x01);
+}
+
+#[test]
+fn ei_takes_effect_after_the_next_instruction() {
+    let mut cpu = code_cpu(&[0xfb, 0x00, 0x00]); // EI; NOP; NOP
+    set_interrupts(&mut cpu, 0x01, 0x01);
+
+    cpu.process();
+    assert!(cpu.ime == ImeState::Pending);
+    cpu.process();
+    assert_eq!(cpu.regs.get(Reg16::PC), 0xc002, "the NOP after EI runs before the interrupt");
+    assert!(cpu.ime == ImeState::Enabled);
+    cpu.process();
+    assert_eq!((cpu.regs.get(Reg16::PC), stacked_pc(&cpu)), (0x0040, 0xc002));
+}
+
+// EI then HALT halts with IME set, so the interrupt that wakes it is serviced. If one is already
+// pending, HALT hits the HALT bug instead and the handler returns to the HALT.
+#[test]
+fn ei_then_halt() {
+    let mut cpu = code_cpu(&[0xfb, 0x76, 0x00]); // EI; HALT; NOP
+    set_interrupts(&mut cpu, 0x01, 0x00);
+    cpu.process();
+    cpu.process();
+    assert!(cpu.halted && cpu.ime == ImeState::Enabled);
+    assert_eq!(cpu.process(), 4);
+    cpu.mem_set(0x01, interrupt::IF_ADDR);
+    cpu.process();
+    assert_eq!((cpu.regs.get(Reg16::PC), stacked_pc(&cpu)), (0x0040, 0xc002));
+
+    let mut cpu = code_cpu(&[0xfb, 0x76, 0x00]);
+    set_interrupts(&mut cpu, 0x01, 0x01);
+    cpu.process();
+    cpu.process();
+    assert!(!cpu.halted);
+    cpu.process();
+    assert_eq!((cpu.regs.get(Reg16::PC), stacked_pc(&cpu)), (0x0040, 0xc001));
+}
+
+#[test]
+fn reti_sets_ime_at_once() {
+    let mut cpu = code_cpu(&[0xd9]); // RETI, to 0xc100
+    cpu.regs.set(Reg16::SP, 0xd000);
+    cpu.mem_set(0x00, 0xd000);
+    cpu.mem_set(0xc1, 0xd001);
+    set_interrupts(&mut cpu, 0x01, 0x01);
+
+    cpu.process();
+    assert!(cpu.ime == ImeState::Enabled);
+    cpu.process();
+    assert_eq!((cpu.regs.get(Reg16::PC), stacked_pc(&cpu)), (0x0040, 0xc100));
+}
+
+// Of the interrupts that are both enabled and requested, the lowest bit goes first, and only its
+// IF bit is cleared.
+#[test]
+fn interrupts_are_taken_in_priority_order() {
+    let mut cpu = code_cpu(&[]);
+    set_interrupts(&mut cpu, 0x14, 0x1d);
+
+    for (vector, iflag) in [(0x0050, 0x19), (0x0060, 0x09)].iter() {
+        cpu.ime = ImeState::Enabled;
+        cpu.process();
+        assert_eq!(cpu.regs.get(Reg16::PC), *vector);
+        assert_eq!(cpu.mem_get(interrupt::IF_ADDR), *iflag);
+        assert!(cpu.ime == ImeState::Disabled);
+    }
+
+    // VBlank and serial are still requested, but not enabled.
+    cpu.ime = ImeState::Enabled;
+    cpu.process();
+    assert_eq!(cpu.regs.get(Reg16::PC), 0x0061);
+}
+
+// Dispatch is two wait states, pushing PC high byte first, and a cycle to jump to the vector.
+#[test]
+fn interrupt_dispatch_takes_five_machine_cycles() {
+    let mut cpu = code_cpu(&[0x00]);
+    set_interrupts(&mut cpu, 0x04, 0x04);
+    cpu.ime = ImeState::Enabled;
+    cpu.mem.lock().unwrap().take_bus_log();
+
+    assert_eq!(cpu.process(), 5 * 4);
+    assert_eq!(cpu.mem.lock().unwrap().take_bus_log(), vec![
+        BusAccess { addr: 0xfffd, val: 0xc0, write: true },
+        BusAccess { addr: 0xfffc, val: 0x00, write: true },
+    ]);
+    assert_eq!(cpu.regs.get(Reg16::PC), 0x0050);
+}
+
+#[test]
+fn halt_wakes_up_without_ime() {
+    let mut cpu = code_cpu(&[0x76, 0x3c]); // HALT; INC A
+    set_interrupts(&mut cpu, 0x04, 0x00);
+    let a = cpu.regs.get(Reg8::A);
+
+    cpu.process();
+    for _ in 0..3 {
+        assert_eq!(cpu.process(), 4);
+        assert_eq!(cpu.regs.get(Reg16::PC), 0xc001);
+    }
+
+    // With IME off the interrupt isn't serviced, the CPU just carries on.
+    cpu.mem_set(0x04, interrupt::IF_ADDR);
+    cpu.process();
+    assert_eq!(cpu.regs.get(Reg16::PC), 0xc002);
+    assert_eq!(cpu.regs.get(Reg8::A), a.wrapping_add(1));
+    assert_eq!(cpu.mem_get(interrupt::IF_ADDR), 0x04);
+}
+
+#[test]
+fn halt_wakes_up_into_the_handler_with_ime() {
+    let mut cpu = code_cpu(&[0x76, 0x3c]); // HALT; INC A
+    set_interrupts(&mut cpu, 0x04, 0x00);
+    cpu.ime = ImeState::Enabled;
+
+    cpu.process();
+    assert_eq!(cpu.process(), 4);
+    cpu.mem_set(0x04, interrupt::IF_ADDR);
+    cpu.process();
+    assert_eq!((cpu.regs.get(Reg16::PC), stacked_pc(&cpu)), (0x0050, 0xc001));
+    assert_eq!(cpu.mem_get(interrupt::IF_ADDR), 0x00);
+}
+
+// HALT with IME off and an interrupt already pending doesn't halt, and the byte after it is read
+// twice.
+#[test]
+fn halt_bug_runs_the_next_byte_twice() {
+    let mut cpu = code_cpu(&[0x76, 0x3c, 0x00]); // HALT; INC A; NOP
+    set_interrupts(&mut cpu, 0x04, 0x04);
+    let a = cpu.regs.get(Reg8::A);
+
+    cpu.process();
+    assert!(!cpu.halted);
+    cpu.process();
+    assert_eq!(cpu.regs.get(Reg16::PC), 0xc001);
+    cpu.process();
+    assert_eq!(cpu.regs.get(Reg16::PC), 0xc002);
+    assert_eq!(cpu.regs.get(Reg8::A), a.wrapping_add(2));
+}
+
+#[test]
+fn stop_skips_its_padding_byte_and_resets_div() {
+    let mut cpu = rom_cpu(&[0x10, 0x00, 0x3c], &RuntimeConfig::new()); // STOP; INC A
+    assert_ne!(cpu.mem_get(DIV_ADDR), 0x00);
+
+    cpu.process();
+    assert!(cpu.is_stopped());
+    assert_eq!(cpu.regs.get(Reg16::PC), 0x0102);
+    assert_eq!(cpu.mem_get(DIV_ADDR), 0x00);
+}
+
+// STOP only wakes up when a pressed button pulls a P1 line low, which needs its group selected.
+#[test]
+fn stop_waits_for_a_selected_button() {
+    // LD A,0x10; LDH (P1),A to select the action buttons; STOP; INC A
+    let mut cpu = rom_cpu(&[0x3e, 0x10, 0xe0, 0x00, 0x10, 0x00, 0x3c], &RuntimeConfig::new());
+    for _ in 0..3 {
+        cpu.process();
+    }
+    assert!(cpu.is_stopped());
+
+    cpu.mem.lock().unwrap().set_button(Button::Down, true);
+    for _ in 0..3 {
+        assert_eq!(cpu.process(), 0);
+        assert_eq!(cpu.regs.get(Reg16::PC), 0x0106);
+    }
+    assert!(cpu.is_stopped());
+
+    cpu.mem.lock().unwrap().set_button(Button::Start, true);
+    cpu.process();
+    assert!(!cpu.is_stopped());
+    assert_eq!(cpu.regs.get(Reg16::PC), 0x0107);
+}
+
+// STOP only switches speed on a CGB with the switch prepared in KEY1, and otherwise stops as usual.
+// Clocks are counted at the system clock rate, so a NOP in double speed is only two of them.
+#[test]
+fn stop_switches_speed_when_key1_asks() {
+    for (cgb, key1, switches) in [(false, 0x01, false), (true, 0x00, false), (true, 0x01, true)].iter() {
+        let mut cpu = code_cpu(&[0x10, 0x00, 0x00]); // STOP; NOP
+        cpu.cgb = *cgb;
+        cpu.mem_set(*key1, KEY1_ADDR);
+        cpu.tick();
+        assert_eq!(cpu.double_speed, *switches);
+        assert_eq!(cpu.is_stopped(), !*switches);
+        assert_eq!(cpu.mem_get(KEY1_ADDR), if *switches { 0x80 } else { *key1 });
+    }
+
+    let mut cpu = code_cpu(&[0x10, 0x00, 0x00, 0x10, 0x00]); // STOP; NOP; STOP
+    cpu.cgb = true;
+    cpu.mem_set(0x01, KEY1_ADDR);
+    cpu.tick();
+    let clocks = cpu.clocks();
+    cpu.tick();
+    assert_eq!(cpu.clocks() - clocks, 2);
+
+    cpu.mem_set(0x01, KEY1_ADDR);
+    cpu.tick();
+    assert!(!cpu.double_speed);
+    assert_eq!(cpu.mem_get(KEY1_ADDR), 0x00);
+    let clocks = cpu.clocks();
+    cpu.tick();
+    assert_eq!(cpu.clocks() - clocks, 4);
+}
+
+// An illegal opcode hangs the CPU where it is, but the timer and the rest of the system keep going.
+#[test]
+fn illegal_opcodes_lock_up_the_cpu() {
+    let mut cfg = RuntimeConfig::new();
+    cfg.headless = true;
+    let mut cpu = rom_cpu(&[0xd3, 0x3c], &cfg); // An illegal opcode; INC A
+    let a = cpu.regs.get(Reg8::A);
+    cpu.process();
+    assert!(cpu.is_locked());
+
+    set_interrupts(&mut cpu, 0x04, 0x04);
+    cpu.ime = ImeState::Enabled;
+    let div = cpu.mem_get(DIV_ADDR);
+    for _ in 0..256 {
+        assert_eq!(cpu.process(), 4);
+    }
+    assert_eq!((cpu.regs.get(Reg16::PC), cpu.regs.get(Reg8::A)), (0x0101, a));
+    assert_eq!(cpu.mem_get(interrupt::IF_ADDR) & 0x1f, 0x04);
+    assert_eq!(cpu.mem_get(DIV_ADDR), div.wrapping_add(4));
+}
+
+// Writes to the cartridge registers only matter to the cache when they select another ROM bank, and
+// then each bank keeps its own blocks. Memory is changed behind the cache's back here, to show which
+// block it runs.
+#[test]
+fn block_cache_keeps_blocks_for_each_rom_bank() {
+    let mem = Mutex::new(Memory::new_flat());
+    let mut cache = BlockCache::new();
+    let operand = |cache: &mut BlockCache, val: u8| {
+        mem.lock().unwrap().set(val, 0x4001, MemClient::CPU);
+        cache.lookup(0x4000, 0, &mem).unwrap().operands[0]
+    };
+    mem.lock().unwrap().set(0x3e, 0x4000, MemClient::CPU); // LD A,d8
+
+    assert_eq!(operand(&mut cache, 1), 1);
+    cache.note_write(0x0000, 0x0a, 0); // Enable cartridge RAM.
+    cache.note_write(0x2000, 0x01, 0); // Select the bank that's already selected.
+    assert_eq!(operand(&mut cache, 2), 1);
+
+    cache.note_write(0x2000, 0x02, 0);
+    assert_eq!(operand(&mut cache, 2), 2);
+    cache.note_write(0x2000, 0x01, 0);
+    assert_eq!(operand(&mut cache, 3), 1);
+
+    // Patching the code drops it from every bank.
+    cache.note_patch(0x4001, 1);
+    assert_eq!(operand(&mut cache, 4), 4);
+    cache.note_write(0x2000, 0x02, 0);
+    assert_eq!(operand(&mut cache, 5), 5);
+}
+
+// STOP has no VBlanks to take the frame input on, so each tick while stopped is a frame instead.
+// A script can then press the button that wakes the CPU up.
+#[test]
+fn scripted_input_wakes_up_stop() {
+    let mut cfg = RuntimeConfig::new();
+    cfg.headless = true;
+    // LD A,0x10; LDH (P1),A to select the action buttons; STOP; INC A
+    let mut cpu = rom_cpu(&[0x3e, 0x10, 0xe0, 0x00, 0x10, 0x00, 0x3c], &cfg);
+    cpu.ppu.set_frame_input(FrameInput::Script(Script::parse("start@3").unwrap()));
+    for _ in 0..3 {
+        cpu.tick();
+    }
+    assert!(cpu.is_stopped());
+
+    let mut ticks = 0;
+    while cpu.is_stopped() {
+        assert!(ticks < 10, "STOP never woke up");
+        cpu.tick();
+        ticks += 1;
+    }
+    assert_eq!(ticks, 4);
+    assert_eq!(cpu.regs.get(Reg16::PC), 0x0107);
+}
+
+// The debugger's assembler writes straight into the loaded ROM, but only where there is ROM.
+#[test]
+fn patches_stay_inside_the_rom() {
+    let mut mem = Memory::new(0x10000);
+    mem.load_rom(vec![0; 0x4000]);
+    mem.set(0x01, BOOT_ADDR, MemClient::CPU);
+
+    assert_eq!(mem.patch(0x3fff, &[0xc3, 0x50]), Err("0x4000 is past the end of the 16384 byte ROM".to_string()));
+    assert_eq!(mem.get(0x3fff, MemClient::CPU), 0x00);
+    assert_eq!(mem.patch(0x3ffe, &[0xc3, 0x50]), Ok(()));
+    assert_eq!(mem.get(0x3fff, MemClient::CPU), 0x50);
+    assert!(mem.patch(0xffff, &[0x00, 0x00]).is_err());
+}
+
+#[test]
+#[ignore = "needs the sm83 suite, clone https://github.com/SingleStepTests/sm83 into tests/sm83 and run with --ignored"]
+fn sm83_single_step_tests() {
+    let dir = suite_dir();
+    assert!(dir.is_dir(), "The sm83 suite isn't at {}. Clone https://github.com/SingleStepTests/sm83 into tests/sm83, \
+            or set SM83_TESTS to its v1 directory.", dir.display());
+
+    let mut cpu = test_cpu();
+    let mut report = Vec::new();
+    let mut failed_opcodes = 0;
+    for opcode in all_opcodes() {
+        let name = if opcode > 0xff { format!("cb {:02x}", opcode & 0xff) } else { format!("{:02x}", opcode) };
+        let path = dir.join(format!("{}.json", name));
+        let vectors: Value = match fs::read_to_string(&path) {
+            Ok(text) => serde_json::from_str(&text).unwrap_or_else(|e| panic!("Bad JSON in {}: {}", path.display(), e)),
+            Err(e) => {
+                failed_opcodes += 1;
+                report.push(format!("[{}] can't read {}: {}", name, path.display(), e));
+                continue;
+            }
+        };
+
+        let failures = run_vectors(&mut cpu, &vectors);
+        if !failures.is_empty() {
+            failed_opcodes += 1;
+            report.push(format!("[{}] {} of {} vectors failed", name, failures.len(), vectors.as_array().unwrap().len()));
+            report.extend(failures.into_iter().take(MAX_REPORTED).map(|f| format!("    {}", f)));
+        }
+    }
+
+    assert!(failed_opcodes == 0, "{} opcodes failed\n{}", failed_opcodes, report.join("\n"));
+}
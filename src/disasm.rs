@@ -0,0 +1,124 @@
+// Disassembly into RGBDS syntax. The mnemonics come from the opcode table, and the placeholders in
+// them (d8, d16, a8, a16 and r8) are filled in from the operand bytes.
+
+use crate::lookup;
+
+mod recursive;
+pub use recursive::write_source_tree;
+
+pub const BANK_SIZE: usize = 0x4000;
+
+// Disassemble the instruction at the start of bytes, where the first byte is at addr. Returns the
+// text and the number of bytes used. Illegal opcodes, and instructions cut off by the end of bytes,
+// come out as data.
+pub fn disassemble(bytes: &[u8], addr: u16) -> (String, usize) {
+    let opcode = match bytes {
+        [] => return (String::new(), 0),
+        [0xcb, op, ..] => 0xcb00 | *op as u16,
+        [op, ..] => *op as u16,
+    };
+    let inst = lookup::get_instruction(opcode);
+    let len = inst.bytes as usize;
+    if inst.name.starts_with("UNKNOWN") || opcode == 0xcb || bytes.len() < len {
+        return (format!("db ${:02x}", bytes[0]), 1);
+    }
+
+    let (mnemonic, operands) = inst.name.split_once(' ').unwrap_or((inst.name, ""));
+    let mut mnemonic = mnemonic.to_lowercase();
+    let operands: Vec<String> = operands.split(',').filter(|op| !op.is_empty()).map(|op| {
+        format_operand(op, &mnemonic, &bytes[1..len], addr.wrapping_add(len as u16))
+    }).collect();
+
+    // RGBDS has no 8-bit offset form of LD, so LD (C),A and LD A,(C) are written as LDH.
+    if operands.iter().any(|op| op == "[c]") { mnemonic = "ldh".to_string(); }
+    // STOP's padding byte is implied.
+    if mnemonic == "stop" { return (mnemonic, len); }
+
+    if operands.is_empty() {
+        (mnemonic, len)
+    } else {
+        (format!("{} {}", mnemonic, operands.join(", ")), len)
+    }
+}
+
+// Turn one operand from the opcode table into RGBDS syntax. next_pc is the address just after the
+// instruction, which relative jumps are taken from.
+fn format_operand(op: &str, mnemonic: &str, data: &[u8], next_pc: u16) -> String {
+    let imm8 = || data.last().copied().unwrap_or(0);
+    let imm16 = || u16::from_le_bytes([data[0], data[1]]);
+    let signed = |val: i8| if val < 0 { format!("-{}", val.unsigned_abs()) } else { format!("+{}", val) };
+
+    match op {
+        "d8" => format!("${:02x}", imm8()),
+        "d16" | "a16" => format!("${:04x}", imm16()),
+        "(a16)" => format!("[${:04x}]", imm16()),
+        "(a8)" => format!("[$ff{:02x}]", imm8()),
+        "r8" if mnemonic == "jr" => format!("${:04x}", next_pc.wrapping_add(imm8() as i8 as u16)),
+        "r8" => signed(imm8() as i8).trim_start_matches('+').to_string(),
+        "SP+r8" => format!("sp{}", signed(imm8() as i8)),
+        _ if op.ends_with('H') && mnemonic == "rst" => format!("${}", op.trim_end_matches('H')),
+        _ if op.starts_with('(') => format!("[{}]", op.trim_matches(|c| c == '(' || c == ')').to_lowercase()),
+        _ => op.to_lowercase(),
+    }
+}
+
+// The address a ROM offset is mapped to: bank 0 at 0x0000, and every other bank at 0x4000.
+pub fn bank_address(offset: usize) -> u16 {
+    if offset < BANK_SIZE { offset as u16 } else { (0x4000 + offset % BANK_SIZE) as u16 }
+}
+
+// Disassemble [from, to) of a ROM bank, as seen by the CPU with that bank mapped in. Each line has
+// the bank:address and the instruction bytes ahead of the code.
+pub fn disassemble_bank(rom: &[u8], bank: usize, from: u16, to: u16) -> Result<Vec<String>, String> {
+    let banks = rom.len().div_ceil(BANK_SIZE);
+    if bank >= banks {
+        return Err(format!("bank {} doesn't exist, the ROM has {} banks", bank, banks));
+    }
+
+    let window = if bank == 0 { 0x0000..0x4000 } else { 0x4000..0x8000 };
+    if !window.contains(&from) || to < from || to > window.end {
+        return Err(format!("bank {} is mapped at 0x{:04x}-0x{:04x}", bank, window.start, window.end - 1));
+    }
+
+    let base = bank * BANK_SIZE - window.start as usize;
+    let end = (base + to as usize).min(rom.len());
+    let mut offset = base + from as usize;
+    let mut lines = Vec::new();
+    while offset < end {
+        let addr = bank_address(offset);
+        let (text, len) = disassemble(&rom[offset..end], addr);
+        let hex: Vec<String> = rom[offset..offset + len].iter().map(|b| format!("{:02x}", b)).collect();
+        lines.push(format!("{:02x}:{:04x}  {:<9} {}", bank, addr, hex.join(" "), text));
+        offset += len;
+    }
+
+    Ok(lines)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formats_operands_in_rgbds_syntax() {
+        let cases: [(&[u8], u16, &str); 12] = [
+            (&[0x3e, 0x05], 0x0150, "ld a, $05"),
+            (&[0xfa, 0x34, 0x12], 0x0150, "ld a, [$1234]"),
+            (&[0x22], 0x0150, "ld [hl+], a"),
+            (&[0xe0, 0x44], 0x0150, "ldh [$ff44], a"),
+            (&[0xf2], 0x0150, "ldh a, [c]"),
+            (&[0x20, 0xfe], 0x0150, "jr nz, $0150"),
+            (&[0xe8, 0xfe], 0x0150, "add sp, -2"),
+            (&[0xf8, 0x05], 0x0150, "ld hl, sp+5"),
+            (&[0xff], 0x0150, "rst $38"),
+            (&[0x10, 0x00], 0x0150, "stop"),
+            (&[0xcb, 0x7c], 0x0150, "bit 7, h"),
+            (&[0xd3], 0x0150, "db $d3"),
+        ];
+
+        for (bytes, addr, text) in cases.iter() {
+            assert_eq!(disassemble(bytes, *addr), (text.to_string(), bytes.len()));
+        }
+        assert_eq!(disassemble(&[0xc3, 0x50], 0x0100), ("db $c3".to_string(), 1));
+    }
+}
@@ -0,0 +1,446 @@
+// Recursive descent disassembly. Code is traced from the entry point and the RST and interrupt
+// vectors, following jumps and calls into other banks where the bank is known, and everything that
+// isn't reached is kept as data. The result is an RGBDS source tree that assembles back into the
+// same ROM.
+//
+// Bank switches are tracked when a constant is stored in the MBC's ROM bank register at
+// 0x2000-0x3FFF, either from A with ld [a16], a or ld [hl], a, or directly with ld [hl], n8. The
+// constants in A and HL are forgotten after a call or rst, since the code called can change them.
+// Which bank a value selects depends on the MBC named by the cartridge type in the header, and
+// switches on MBCs that aren't handled leave the bank unknown.
+// Jumps into 0x4000-0x7FFF from bank 0 without a known bank can't be followed, unless the ROM only
+// has the one switchable bank.
+
+use std::collections::{BTreeMap, HashSet};
+use std::fmt::Write as _;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use super::{disassemble, bank_address, BANK_SIZE};
+use crate::lookup;
+
+// Where tracing starts, and the labels for those addresses.
+const ENTRY_POINTS: [(u16, &str); 14] = [
+    (0x0000, "RST_00"), (0x0008, "RST_08"), (0x0010, "RST_10"), (0x0018, "RST_18"),
+    (0x0020, "RST_20"), (0x0028, "RST_28"), (0x0030, "RST_30"), (0x0038, "RST_38"),
+    (0x0040, "VBlankInterrupt"), (0x0048, "LCDCInterrupt"), (0x0050, "TimerOverflowInterrupt"),
+    (0x0058, "SerialTransferCompleteInterrupt"), (0x0060, "JoypadTransitionInterrupt"),
+    (0x0100, "Boot"),
+];
+
+const DATA_PER_LINE: usize = 8;
+const MIN_FILL_RUN: usize = 16;
+
+// Label kinds, in increasing priority when more than one applies to an address.
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+enum LabelKind {
+    Relative,
+    Jump,
+    Call,
+}
+
+pub struct Summary {
+    pub banks: usize,
+    pub code_bytes: usize,
+    pub labels: usize,
+}
+
+// The memory bank controllers that ROM bank switches are understood for.
+#[derive(Copy, Clone, PartialEq, Debug)]
+enum Mbc {
+    None,
+    Mbc1,
+    Mbc3,
+    Mbc5,
+    Unknown,
+}
+
+impl Mbc {
+    // The MBC for the cartridge type byte in the header.
+    fn from_header(rom: &[u8]) -> Mbc {
+        match rom.get(0x147) {
+            Some(0x00) | Some(0x08) | Some(0x09) => Mbc::None,
+            Some(0x01..=0x03) => Mbc::Mbc1,
+            Some(0x0f..=0x13) => Mbc::Mbc3,
+            Some(0x19..=0x1e) => Mbc::Mbc5,
+            _ => Mbc::Unknown,
+        }
+    }
+
+    // The ROM bank selected by writing val to addr in the bank register, with bank selected before
+    // it, or None if that isn't known. MBC1 and MBC3 turn bank 0 into bank 1, but MBC5 can map bank
+    // 0 there, and uses 0x3000-0x3FFF for bit 8 of the bank.
+    fn select_bank(self, addr: u16, val: u8, bank: Option<usize>, banks: usize) -> Option<usize> {
+        let selected = match self {
+            Mbc::None => 1,
+            Mbc::Mbc1 => (val & 0x1f).max(1) as usize,
+            Mbc::Mbc3 => (val & 0x7f).max(1) as usize,
+            Mbc::Mbc5 if addr < 0x3000 => bank.map_or(0, |bank| bank & 0x100) | val as usize,
+            Mbc::Mbc5 => (bank? & 0xff) | ((val as usize & 1) << 8),
+            Mbc::Unknown => return None,
+        };
+        Some(selected % banks)
+    }
+}
+
+struct Tracer<'a> {
+    rom: &'a [u8],
+    banks: usize,
+    mbc: Mbc,
+    code: Vec<u8>, // Length of the instruction starting at each ROM offset, 0 if it isn't code.
+    covered: Vec<bool>, // Set for every byte of every instruction.
+    labels: BTreeMap<usize, String>,
+    queued: HashSet<usize>,
+    work: Vec<(usize, Option<usize>)>, // ROM offsets to trace from, and the known ROM bank there.
+}
+
+impl<'a> Tracer<'a> {
+    fn new(rom: &'a [u8]) -> Tracer<'a> {
+        Tracer {
+            rom,
+            banks: rom.len().div_ceil(BANK_SIZE),
+            mbc: Mbc::from_header(rom),
+            code: vec![0; rom.len()],
+            covered: vec![false; rom.len()],
+            labels: BTreeMap::new(),
+            queued: HashSet::new(),
+            work: Vec::new(),
+        }
+    }
+
+    // The ROM offset for addr, seen from code in the given bank with the given switchable bank.
+    fn resolve(&self, addr: u16, cur_bank: usize, rom_bank: Option<usize>) -> Option<usize> {
+        let bank = match addr {
+            0x0000..=0x3FFF => 0,
+            0x4000..=0x7FFF if cur_bank > 0 => cur_bank,
+            0x4000..=0x7FFF if self.banks == 2 => 1,
+            0x4000..=0x7FFF => rom_bank?,
+            _ => return None,
+        };
+        let offset = bank * BANK_SIZE + (addr as usize % BANK_SIZE);
+        if offset < self.rom.len() { Some(offset) } else { None }
+    }
+
+    fn queue(&mut self, offset: usize, rom_bank: Option<usize>) {
+        if self.queued.insert(offset) {
+            self.work.push((offset, rom_bank));
+        }
+    }
+
+    fn add_label(&mut self, offset: usize, kind: LabelKind) {
+        let prefix = match kind {
+            LabelKind::Relative => "jr",
+            LabelKind::Jump => "Jump",
+            LabelKind::Call => "Call",
+        };
+        let name = format!("{}_{:03x}_{:04x}", prefix, offset / BANK_SIZE, bank_address(offset));
+        let better = match self.labels.get(&offset) {
+            Some(old) => label_kind(old).is_some_and(|old_kind| old_kind < kind),
+            None => true,
+        };
+        if better { self.labels.insert(offset, name); }
+    }
+
+    fn trace(&mut self) {
+        for (addr, name) in ENTRY_POINTS.iter() {
+            if (*addr as usize) < self.rom.len() {
+                self.labels.insert(*addr as usize, name.to_string());
+                self.queue(*addr as usize, None);
+            }
+        }
+
+        while let Some((start, rom_bank)) = self.work.pop() {
+            self.trace_from(start, rom_bank);
+        }
+
+        // Jumps into the middle of an instruction can't have a label there, they're left as numbers.
+        let (code, covered) = (&self.code, &self.covered);
+        self.labels.retain(|offset, _| code[*offset] > 0 || !covered[*offset]);
+    }
+
+    // Follow straight-line code from start until it jumps away, returns, or runs into something
+    // that can't be code.
+    fn trace_from(&mut self, start: usize, mut rom_bank: Option<usize>) {
+        let cur_bank = start / BANK_SIZE;
+        let bank_end = ((cur_bank + 1) * BANK_SIZE).min(self.rom.len());
+        let (mbc, banks) = (self.mbc, self.banks.max(1));
+        let select_bank = |addr: u16, val: Option<u8>, bank| mbc.select_bank(addr, val?, bank, banks);
+        let bank_register = |addr: u16| (0x2000..0x4000).contains(&addr);
+        let mut a_value: Option<u8> = None;
+        let mut hl_value: Option<u16> = None;
+        let mut offset = start;
+
+        while offset < bank_end && !self.covered[offset] {
+            let addr = bank_address(offset);
+            let bytes = &self.rom[offset..bank_end];
+            let opcode = match bytes {
+                [0xcb, op, ..] => 0xcb00 | *op as u16,
+                [op, ..] => *op as u16,
+                [] => break,
+            };
+            let inst = lookup::get_instruction(opcode);
+            let len = inst.bytes as usize;
+            if !is_reassemblable(bytes, opcode) || self.covered[offset..offset + len].iter().any(|c| *c) {
+                break;
+            }
+            self.code[offset] = len as u8;
+            self.covered[offset..offset + len].fill(true);
+
+            // Note constants loaded into A and HL, and where they're written to the ROM bank register.
+            let imm16 = if len == 3 { u16::from_le_bytes([bytes[1], bytes[2]]) } else { 0 };
+            let hl_bank_register = hl_value.filter(|hl| bank_register(*hl));
+            match opcode {
+                0x3e => a_value = Some(bytes[1]),
+                0xaf => a_value = Some(0),
+                0xea if bank_register(imm16) => rom_bank = select_bank(imm16, a_value, rom_bank),
+                0x77 | 0x36 => if let Some(hl) = hl_bank_register {
+                    let val = if opcode == 0x77 { a_value } else { Some(bytes[1]) };
+                    rom_bank = select_bank(hl, val, rom_bank);
+                },
+                _ if writes_a(inst.name) => a_value = None,
+                _ => (),
+            }
+            if opcode == 0x21 {
+                hl_value = Some(imm16);
+            } else if writes_hl(opcode, inst.name) {
+                hl_value = None;
+            }
+            if is_call(opcode) {
+                a_value = None;
+                hl_value = None;
+            }
+
+            let next = addr.wrapping_add(len as u16);
+            let target = match opcode {
+                0xc3 | 0xc2 | 0xca | 0xd2 | 0xda => Some((imm16, LabelKind::Jump)),
+                0xcd | 0xc4 | 0xcc | 0xd4 | 0xdc => Some((imm16, LabelKind::Call)),
+                0x18 | 0x20 | 0x28 | 0x30 | 0x38 => Some((next.wrapping_add(bytes[1] as i8 as u16), LabelKind::Relative)),
+                _ => None,
+            };
+            if let Some((target, kind)) = target {
+                if let Some(target) = self.resolve(target, cur_bank, rom_bank) {
+                    self.add_label(target, kind);
+                    self.queue(target, rom_bank);
+                }
+            }
+
+            // Unconditional jumps and returns don't fall through to the next instruction.
+            if matches!(opcode, 0xc3 | 0x18 | 0xc9 | 0xd9 | 0xe9) { break; }
+            offset += len;
+        }
+    }
+
+    // The source for one bank, with labels, code, and data for everything else.
+    fn bank_source(&self, bank: usize) -> String {
+        let start = bank * BANK_SIZE;
+        let end = ((bank + 1) * BANK_SIZE).min(self.rom.len());
+        let mut out = String::new();
+        if bank == 0 {
+            writeln!(out, "SECTION \"ROM Bank $000\", ROM0[$0000]").unwrap();
+        } else {
+            writeln!(out, "SECTION \"ROM Bank ${:03x}\", ROMX[$4000], BANK[${:x}]", bank, bank).unwrap();
+        }
+
+        let mut offset = start;
+        let mut data = Vec::new();
+        while offset < end {
+            let label = self.labels.get(&offset);
+            let len = self.code[offset] as usize;
+            if !data.is_empty() && (label.is_some() || len > 0 || data.len() == DATA_PER_LINE) {
+                write_data(&mut out, &data);
+                data.clear();
+            }
+            if let Some(label) = label {
+                writeln!(out, "\n{}:", label).unwrap();
+            }
+
+            if len == 0 {
+                // Long runs of the same byte, usually padding, are written as one ds.
+                let byte = self.rom[offset];
+                let run = (offset..end).take_while(|o| {
+                    self.rom[*o] == byte && self.code[*o] == 0 && (*o == offset || !self.labels.contains_key(o))
+                }).count();
+                if run >= MIN_FILL_RUN {
+                    if !data.is_empty() { write_data(&mut out, &data); }
+                    data.clear();
+                    writeln!(out, "    ds {}, ${:02x}", run, byte).unwrap();
+                    offset += run;
+                } else {
+                    data.push(byte);
+                    offset += 1;
+                }
+                continue;
+            }
+
+            let addr = bank_address(offset);
+            let (mut text, _) = disassemble(&self.rom[offset..offset + len], addr);
+            if let Some(label) = self.target_label(offset, bank) {
+                if let Some((inst, _)) = text.rsplit_once(' ') {
+                    text = format!("{} {}", inst, label);
+                }
+            }
+            writeln!(out, "    {}", text).unwrap();
+            offset += len;
+        }
+        if !data.is_empty() { write_data(&mut out, &data); }
+
+        out
+    }
+
+    // The label for the target of the jump or call at offset, if it has one.
+    fn target_label(&self, offset: usize, bank: usize) -> Option<&String> {
+        let bytes = &self.rom[offset..];
+        let addr = bank_address(offset);
+        let target = match bytes[0] {
+            0xc3 | 0xc2 | 0xca | 0xd2 | 0xda | 0xcd | 0xc4 | 0xcc | 0xd4 | 0xdc => u16::from_le_bytes([bytes[1], bytes[2]]),
+            0x18 | 0x20 | 0x28 | 0x30 | 0x38 => addr.wrapping_add(2).wrapping_add(bytes[1] as i8 as u16),
+            _ => return None,
+        };
+
+        // The switchable bank isn't known here, so find whichever bank the tracer labelled.
+        match target {
+            0x0000..=0x3FFF => self.labels.get(&(target as usize)),
+            0x4000..=0x7FFF if bank > 0 => self.labels.get(&(bank * BANK_SIZE + target as usize % BANK_SIZE)),
+            0x4000..=0x7FFF => {
+                let mut found = (1..self.banks).filter_map(|b| self.labels.get(&(b * BANK_SIZE + target as usize % BANK_SIZE)));
+                let first = found.next();
+                if found.next().is_some() { None } else { first }
+            },
+            _ => None,
+        }
+    }
+}
+
+fn label_kind(name: &str) -> Option<LabelKind> {
+    match name.split('_').next() {
+        Some("jr") => Some(LabelKind::Relative),
+        Some("Jump") => Some(LabelKind::Jump),
+        Some("Call") => Some(LabelKind::Call),
+        _ => None,
+    }
+}
+
+// Whether an instruction might change A, which loses track of the constant it held.
+fn writes_a(name: &str) -> bool {
+    const PREFIXES: [&str; 17] = ["LD A,", "LDH A,", "POP AF", "ADD A,", "ADC A,", "SUB ", "SBC A,", "AND ",
+                                  "XOR ", "OR ", "INC A", "DEC A", "CPL", "DAA", "RLA", "RRA", "RLCA"];
+    PREFIXES.iter().any(|p| name.starts_with(p)) || name == "RRCA" ||
+        (name.ends_with('A') && !name.starts_with("BIT") && !name.starts_with("LD") && !name.starts_with("CP"))
+}
+
+// Whether an instruction changes H or L. Only the CB prefixed ones name the register last, the
+// unprefixed instructions ending in H or L only read it.
+fn writes_hl(opcode: u16, name: &str) -> bool {
+    const PREFIXES: [&str; 9] = ["LD HL,", "LD H,", "LD L,", "POP HL", "ADD HL,", "INC H", "INC L", "DEC H", "DEC L"];
+    PREFIXES.iter().any(|p| name.starts_with(p)) || name.contains("(HL+)") || name.contains("(HL-)") ||
+        (opcode > 0xff && !name.starts_with("BIT") && (name.ends_with('H') || name.ends_with('L')))
+}
+
+// Calls and RSTs, which run code that can change any register.
+fn is_call(opcode: u16) -> bool {
+    matches!(opcode, 0xcd | 0xc4 | 0xcc | 0xd4 | 0xdc | 0xc7 | 0xcf | 0xd7 | 0xdf | 0xe7 | 0xef | 0xf7 | 0xff)
+}
+
+// Whether bytes start with an instruction that RGBDS assembles back into the same bytes. Illegal
+// opcodes, truncated instructions and STOP with a non-zero padding byte are kept as data.
+fn is_reassemblable(bytes: &[u8], opcode: u16) -> bool {
+    let inst = lookup::get_instruction(opcode);
+    !inst.name.starts_with("UNKNOWN") && opcode != 0xcb && bytes.len() >= inst.bytes as usize &&
+        !(opcode == 0x10 && bytes[1] != 0)
+}
+
+fn write_data(out: &mut String, data: &[u8]) {
+    let bytes: Vec<String> = data.iter().map(|b| format!("${:02x}", b)).collect();
+    writeln!(out, "    db {}", bytes.join(", ")).unwrap();
+}
+
+// Disassemble a whole ROM into dir, as game.asm and a file for each bank that it includes.
+pub fn write_source_tree(rom: &[u8], dir: &Path) -> io::Result<Summary> {
+    let mut tracer = Tracer::new(rom);
+    tracer.trace();
+
+    fs::create_dir_all(dir)?;
+    let mut main = String::from("; Build with: rgbasm -o game.o game.asm && rgblink -o game.gb game.o\n\n");
+    for bank in 0..tracer.banks {
+        let file = format!("bank_{:03x}.asm", bank);
+        fs::write(dir.join(&file), tracer.bank_source(bank))?;
+        writeln!(main, "INCLUDE \"{}\"", file).unwrap();
+    }
+    fs::write(dir.join("game.asm"), main)?;
+
+    Ok(Summary {
+        banks: tracer.banks,
+        code_bytes: tracer.code.iter().map(|len| *len as usize).sum(),
+        labels: tracer.labels.len(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn follows_calls_into_switched_banks() {
+        let mut rom = vec![0xd3; 4 * BANK_SIZE];
+        rom[0x147] = 0x01; // MBC1
+        // ld a, 2; ld [$2000], a; call $4000; jr @-1 (into the middle of itself)
+        rom[0x100..0x10a].copy_from_slice(&[0x3e, 0x02, 0xea, 0x00, 0x20, 0xcd, 0x00, 0x40, 0x18, 0xff]);
+        rom[2 * BANK_SIZE] = 0xc9;
+
+        let mut tracer = Tracer::new(&rom);
+        tracer.trace();
+        let bank0 = tracer.bank_source(0);
+        let bank1 = tracer.bank_source(1);
+        let bank2 = tracer.bank_source(2);
+
+        assert!(bank0.contains("Boot:\n    ld a, $02\n    ld [$2000], a\n    call Call_002_4000\n    jr $0109\n"));
+        assert!(bank1.starts_with("SECTION \"ROM Bank $001\", ROMX[$4000], BANK[$1]\n    ds 16384, $d3\n"));
+        assert!(bank2.contains("\nCall_002_4000:\n    ret\n    ds 16383, $d3\n"));
+        assert!(!tracer.labels.contains_key(&0x109));
+    }
+
+    #[test]
+    fn tracks_bank_writes_through_hl_until_a_call() {
+        let mut rom = vec![0xd3; 4 * BANK_SIZE];
+        rom[0x147] = 0x01; // MBC1
+        // ld a, 2; ld hl, $2000; ld [hl], a; call $4000; ld [$2000], a; jp $4100
+        rom[0x100..0x10f].copy_from_slice(&[0x3e, 0x02, 0x21, 0x00, 0x20, 0x77, 0xcd, 0x00, 0x40,
+                                            0xea, 0x00, 0x20, 0xc3, 0x00, 0x41]);
+        rom[2 * BANK_SIZE] = 0xc9;
+
+        let mut tracer = Tracer::new(&rom);
+        tracer.trace();
+        let bank0 = tracer.bank_source(0);
+
+        // The call can change A, so the bank written after it isn't known and the jump isn't followed.
+        assert!(bank0.contains("    ld [hl], a\n    call Call_002_4000\n    ld [$2000], a\n    jp $4100\n"), "{}", bank0);
+        assert!(tracer.bank_source(2).contains("\nCall_002_4000:\n    ret\n"));
+        assert_eq!(tracer.labels.len(), ENTRY_POINTS.len() + 1);
+    }
+
+    #[test]
+    fn selects_banks_the_way_the_mbc_does() {
+        let mut rom = vec![0; 0x150];
+        let mbcs = [0x00, 0x03, 0x13, 0x19, 0x05, 0xfc].map(|cart_type| {
+            rom[0x147] = cart_type;
+            Mbc::from_header(&rom)
+        });
+        assert_eq!(mbcs, [Mbc::None, Mbc::Mbc1, Mbc::Mbc3, Mbc::Mbc5, Mbc::Unknown, Mbc::Unknown]);
+        assert_eq!(Mbc::from_header(&rom[..0x100]), Mbc::Unknown);
+
+        // Bank 0 is bank 1 on MBC1 and MBC3, but not on MBC5. MBC1 only has five bits.
+        assert_eq!(Mbc::Mbc1.select_bank(0x2000, 0x00, None, 64), Some(1));
+        assert_eq!(Mbc::Mbc1.select_bank(0x2000, 0x22, None, 64), Some(2));
+        assert_eq!(Mbc::Mbc3.select_bank(0x2000, 0x00, None, 128), Some(1));
+        assert_eq!(Mbc::Mbc3.select_bank(0x2000, 0x45, None, 128), Some(0x45));
+        assert_eq!(Mbc::Mbc5.select_bank(0x2000, 0x00, None, 512), Some(0));
+        assert_eq!(Mbc::Mbc5.select_bank(0x3000, 0x01, Some(0x12), 512), Some(0x112));
+        assert_eq!(Mbc::Mbc5.select_bank(0x3000, 0x01, None, 512), None);
+        assert_eq!(Mbc::Mbc5.select_bank(0x2000, 0x34, Some(0x112), 512), Some(0x134));
+        assert_eq!(Mbc::Unknown.select_bank(0x2000, 0x02, None, 4), None);
+
+        // The bank wraps around the banks the ROM has.
+        assert_eq!(Mbc::Mbc1.select_bank(0x2000, 0x05, None, 4), Some(1));
+    }
+}
@@ -0,0 +1,188 @@
+// Turning keyboard and game controller events into joypad presses. Controllers can be plugged in and
+// out while the emulator runs, SDL reports the ones already connected at startup the same way.
+//
+// Every keyboard, controller and analog stick keeps its own set of held buttons, and the joypad sees
+// them combined, so letting go of a key doesn't release a button that's still held on a controller.
+
+use std::collections::HashMap;
+
+use sdl2::GameControllerSubsystem;
+use sdl2::controller::{Axis, GameController};
+use sdl2::event::Event;
+
+use crate::config::{Action, Binding, Bindings, Hotkey};
+use crate::joypad::Button;
+
+// The left stick presses a direction once it's pushed past halfway, and lets go once it's back
+// under a quarter, so a stick resting near the threshold doesn't flicker.
+const STICK_PRESS: i16 = 16384;
+const STICK_RELEASE: i16 = 8192;
+
+#[derive(Copy, Clone, PartialEq, Eq, Hash)]
+enum Source {
+    Keyboard,
+    Pad(u32),   // A controller's buttons, by joystick instance id.
+    Stick(u32), // A controller's left stick.
+}
+
+pub struct Input {
+    bindings:    Bindings,
+    hotkeys:     Vec<Hotkey>,                // Hotkeys pressed since they were last taken.
+    subsystem:   Option<GameControllerSubsystem>,
+    controllers: HashMap<u32, GameController>,
+    sticks:      HashMap<u32, (i8, i8)>, // The direction each stick is pushed in, on the X and Y axes.
+    held:        HashMap<Source, u8>,    // The buttons held through each source, as joypad masks.
+    state:       u8                      // All of the held buttons combined.
+}
+
+impl Input {
+    // Controllers are only supported when SDL's game controller subsystem could be started.
+    pub fn new(bindings: Bindings, subsystem: Option<GameControllerSubsystem>) -> Input {
+        Input {
+            bindings,
+            hotkeys: Vec::new(),
+            subsystem,
+            controllers: HashMap::new(),
+            sticks: HashMap::new(),
+            held: HashMap::new(),
+            state: 0
+        }
+    }
+
+    // Handle an SDL event, returning the joypad buttons it pressed or released.
+    pub fn handle_event(&mut self, event: &Event) -> Vec<(Button, bool)> {
+        match event {
+            Event::KeyDown { keycode: Some(key), repeat: false, .. } => {
+                self.press(Source::Keyboard, Binding::Key(*key), true);
+            },
+            Event::KeyUp { keycode: Some(key), .. } => {
+                self.press(Source::Keyboard, Binding::Key(*key), false);
+            },
+            Event::ControllerDeviceAdded { which, .. } => self.connect(*which),
+            Event::ControllerDeviceRemoved { which, .. } => {
+                if let Some(controller) = self.controllers.remove(which) {
+                    println!("Disconnected controller: {}", controller.name());
+                }
+                self.sticks.remove(which);
+                self.held.remove(&Source::Pad(*which));
+                self.held.remove(&Source::Stick(*which));
+            },
+            Event::ControllerButtonDown { which, button, .. } => {
+                self.press(Source::Pad(*which), Binding::Pad(*button), true);
+            },
+            Event::ControllerButtonUp { which, button, .. } => {
+                self.press(Source::Pad(*which), Binding::Pad(*button), false);
+            },
+            Event::ControllerAxisMotion { which, axis, value, .. } => self.move_stick(*which, *axis, *value),
+            _ => (),
+        }
+        self.changes()
+    }
+
+    fn connect(&mut self, index: u32) {
+        let subsystem = match &self.subsystem {
+            Some(subsystem) => subsystem,
+            None => return,
+        };
+        match subsystem.open(index) {
+            Ok(controller) => {
+                println!("Connected controller: {}", controller.name());
+                self.controllers.insert(controller.instance_id(), controller);
+            },
+            Err(e) => eprintln!("Error opening controller {}: {}", index, e),
+        }
+    }
+
+    fn move_stick(&mut self, which: u32, axis: Axis, value: i16) {
+        let (x, y) = self.sticks.get(&which).copied().unwrap_or((0, 0));
+        let (x, y) = match axis {
+            Axis::LeftX => (stick_direction(x, value), y),
+            Axis::LeftY => (x, stick_direction(y, value)),
+            _ => return,
+        };
+        self.sticks.insert(which, (x, y));
+
+        let mut mask = 0;
+        if x > 0 { mask |= Button::Right.mask(); }
+        if x < 0 { mask |= Button::Left.mask(); }
+        if y > 0 { mask |= Button::Down.mask(); }
+        if y < 0 { mask |= Button::Up.mask(); }
+        self.held.insert(Source::Stick(which), mask);
+    }
+
+    // Take the hotkeys pressed since the last call.
+    pub fn take_hotkeys(&mut self) -> Vec<Hotkey> {
+        std::mem::take(&mut self.hotkeys)
+    }
+
+    // Press or release whatever's bound to an input. Hotkeys fire when they're pressed.
+    fn press(&mut self, source: Source, input: Binding, pressed: bool) {
+        let mut buttons = 0;
+        for action in self.bindings.actions(input) {
+            match action {
+                Action::Button(button) => buttons |= button.mask(),
+                Action::Hotkey(hotkey) if pressed => self.hotkeys.push(hotkey),
+                Action::Hotkey(_) => (),
+            }
+        }
+
+        let held = self.held.entry(source).or_insert(0);
+        if pressed { *held |= buttons; } else { *held &= !buttons; }
+    }
+
+    // The buttons that changed since the last call, from all of the sources combined.
+    fn changes(&mut self) -> Vec<(Button, bool)> {
+        let state = self.held.values().fold(0, |all, held| all | held);
+        let changed = state ^ self.state;
+        self.state = state;
+        Button::ALL.iter().filter(|b| changed & b.mask() != 0).map(|b| (*b, state & b.mask() != 0)).collect()
+    }
+}
+
+// The direction a stick axis is pushed in, given the one it was pushed in before.
+fn stick_direction(current: i8, value: i16) -> i8 {
+    if value >= STICK_PRESS {
+        1
+    } else if value <= -STICK_PRESS {
+        -1
+    } else if (value as i32).abs() < STICK_RELEASE as i32 {
+        0
+    } else {
+        current
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sdl2::keyboard::Keycode;
+
+    fn axis(value: i16) -> Event {
+        Event::ControllerAxisMotion { timestamp: 0, which: 1, axis: Axis::LeftX, value }
+    }
+
+    #[test]
+    fn combines_sources_and_thresholds_the_stick() {
+        let mut input = Input::new(Bindings::defaults(), None);
+        let key = |keycode, down| if down {
+            Event::KeyDown { timestamp: 0, window_id: 0, keycode: Some(keycode), scancode: None, keymod: sdl2::keyboard::Mod::NOMOD, repeat: false }
+        } else {
+            Event::KeyUp { timestamp: 0, window_id: 0, keycode: Some(keycode), scancode: None, keymod: sdl2::keyboard::Mod::NOMOD, repeat: false }
+        };
+
+        assert_eq!(input.handle_event(&key(Keycode::Left, true)), vec![(Button::Left, true)]);
+        assert_eq!(input.handle_event(&axis(-20000)), vec![]);
+        assert_eq!(input.handle_event(&key(Keycode::Left, false)), vec![]);
+
+        // Easing off the stick only lets go once it's under the release threshold.
+        assert_eq!(input.handle_event(&axis(-10000)), vec![]);
+        assert_eq!(input.handle_event(&axis(-4000)), vec![(Button::Left, false)]);
+        assert_eq!(input.handle_event(&axis(20000)), vec![(Button::Right, true)]);
+
+        let unplug = Event::ControllerDeviceRemoved { timestamp: 0, which: 1 };
+        assert_eq!(input.handle_event(&unplug), vec![(Button::Right, false)]);
+
+        assert_eq!(input.handle_event(&key(Keycode::Escape, true)), vec![]);
+        assert_eq!(input.take_hotkeys(), vec![Hotkey::Quit]);
+    }
+}
@@ -0,0 +1,55 @@
+// Interrupt sources and the IE/IF registers used to enable and request them. Peripherals request
+// an interrupt by setting its bit in IF, and the CPU services the highest priority interrupt that is
+// both requested and enabled in IE, as long as IME is set.
+
+use std::fmt::{Display, Formatter, Result};
+
+pub const IE_ADDR: u16 = 0xFFFF; // IE - Interrupt enable register
+pub const IF_ADDR: u16 = 0xFF0F; // IF - Interrupt request (flag) register
+
+// Interrupts, ordered from highest to lowest priority. The value is the bit index in IE and IF.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Interrupt {
+    VBlank  = 0,
+    LcdStat = 1,
+    Timer   = 2,
+    Serial  = 3,
+    Joypad  = 4,
+}
+
+impl Interrupt {
+    const PRIORITY: [Interrupt; 5] = [
+        Interrupt::VBlank,
+        Interrupt::LcdStat,
+        Interrupt::Timer,
+        Interrupt::Serial,
+        Interrupt::Joypad,
+    ];
+
+    // Bit mask for this interrupt in the IE and IF registers.
+    pub fn mask(self) -> u8 {
+        1 << (self as u8)
+    }
+
+    // Address the CPU jumps to when this interrupt is serviced.
+    pub fn vector(self) -> u16 {
+        0x40 + 0x8 * (self as u16)
+    }
+
+    // Return the highest priority interrupt that is both enabled and requested, if any.
+    pub fn highest_pending(ie: u8, iflag: u8) -> Option<Interrupt> {
+        Interrupt::PRIORITY.iter().cloned().find(|ir| (ie & iflag & ir.mask()) != 0)
+    }
+}
+
+impl Display for Interrupt {
+    fn fmt(&self, f: &mut Formatter) -> Result {
+        match *self {
+            Interrupt::VBlank  => write!(f, "VBlank"),
+            Interrupt::LcdStat => write!(f, "LCD STAT"),
+            Interrupt::Timer   => write!(f, "Timer"),
+            Interrupt::Serial  => write!(f, "Serial"),
+            Interrupt::Joypad  => write!(f, "Joypad"),
+        }
+    }
+}
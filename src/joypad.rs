@@ -0,0 +1,104 @@
+// The joypad, read through P1. The eight buttons are wired as a 2x4 matrix: writing 0 to bit 4 of P1
+// selects the d-pad and writing 0 to bit 5 selects the buttons, and the low four bits then read 0
+// for each pressed button in the selected groups. The joypad interrupt is requested whenever one of
+// those four lines goes from high to low.
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Button {
+    Right  = 0,
+    Left   = 1,
+    Up     = 2,
+    Down   = 3,
+    A      = 4,
+    B      = 5,
+    Select = 6,
+    Start  = 7,
+}
+
+impl Button {
+    pub const ALL: [Button; 8] = [
+        Button::Right, Button::Left, Button::Up, Button::Down, Button::A, Button::B, Button::Select, Button::Start,
+    ];
+    pub const NAMES: [&'static str; 8] = ["right", "left", "up", "down", "a", "b", "select", "start"];
+
+    // Parse a button name like "start" or "A".
+    pub fn from_name(name: &str) -> Option<Button> {
+        Button::NAMES.iter().position(|n| n.eq_ignore_ascii_case(name)).map(|i| Button::ALL[i])
+    }
+
+    // Bit mask of this button in the joypad's pressed state. The low nibble is the d-pad and the
+    // high nibble the buttons, each in the order they appear on P1's low four bits.
+    pub fn mask(self) -> u8 {
+        1 << (self as u8)
+    }
+}
+
+pub struct Joypad {
+    pressed: u8, // One bit per button, set while it's held down.
+    select:  u8  // Bits 4 and 5 of P1, a 0 selects that group.
+}
+
+impl Joypad {
+    pub fn new() -> Joypad {
+        Joypad {
+            pressed: 0,
+            select:  0x30
+        }
+    }
+
+    pub fn read(&self) -> u8 {
+        0xC0 | self.select | self.lines()
+    }
+
+    // Handle a write to P1, returning true when it requests the joypad interrupt. Only the select
+    // bits are writable, but selecting a group with a button held pulls its line low.
+    pub fn write(&mut self, val: u8) -> bool {
+        let before = self.lines();
+        self.select = val & 0x30;
+        Joypad::falling_edge(before, self.lines())
+    }
+
+    // Press or release a button, returning true when it requests the joypad interrupt.
+    pub fn set_button(&mut self, button: Button, pressed: bool) -> bool {
+        let before = self.lines();
+        if pressed {
+            self.pressed |= button.mask();
+        } else {
+            self.pressed &= !button.mask();
+        }
+        Joypad::falling_edge(before, self.lines())
+    }
+
+    // The low four bits of P1, with a 0 for each held button in a selected group.
+    fn lines(&self) -> u8 {
+        let mut low = 0;
+        if self.select & 0x10 == 0 { low |= self.pressed & 0x0F; }
+        if self.select & 0x20 == 0 { low |= self.pressed >> 4; }
+        !low & 0x0F
+    }
+
+    fn falling_edge(before: u8, after: u8) -> bool {
+        (before & !after) != 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn select_lines_pick_the_group_and_presses_interrupt() {
+        let mut joypad = Joypad::new();
+        assert!(!joypad.write(0x20)); // Select the d-pad.
+        assert!(!joypad.set_button(Button::A, true));
+        assert_eq!(joypad.read(), 0xEF);
+        assert!(joypad.set_button(Button::Down, true));
+        assert_eq!(joypad.read(), 0xE7);
+
+        // Switching to the buttons with A held pulls its line low instead.
+        assert!(joypad.write(0x10));
+        assert_eq!(joypad.read(), 0xDE);
+        assert!(!joypad.set_button(Button::A, false));
+        assert_eq!(joypad.read(), 0xDF);
+    }
+}
@@ -5,6 +5,19 @@ mod window;
 mod memory;
 mod util;
 mod lookup;
+mod interrupt;
+mod serial;
+mod timer;
+mod joypad;
+mod input;
+mod config;
+mod movie;
+mod script;
+mod runner;
+mod model;
+mod disasm;
+mod asm;
+mod trace;
 
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
@@ -13,6 +26,7 @@ use std::collections::HashSet;
 use std::thread;
 use std::time;
 use std::fs;
+use std::path::Path;
 use chrono::{Utc, Datelike, Timelike};
 
 pub struct RuntimeConfig {
@@ -21,6 +35,22 @@ pub struct RuntimeConfig {
     killpoint: Option<u16>,
     dump_mem: bool,
     verbose:  bool,
+    headless: bool,
+    serial_test: bool,
+    mooneye_test: bool,
+    benchmark: bool,
+    block_cache: bool,
+    model: Option<model::Model>,
+    time_limit: u64,
+    trace_file: Option<String>,
+    trace_ly: bool,
+    trace_clocks: bool,
+    break_at_instruction: Option<u64>,
+    config_file: Option<String>,
+    record_file: Option<String>,
+    play_file: Option<String>,
+    input_script: Option<String>,
+    input_file: Option<String>,
 }
 
 impl RuntimeConfig {
@@ -31,20 +61,282 @@ impl RuntimeConfig {
             killpoint: None,
             dump_mem: false,
             verbose:  false,
+            headless: false,
+            serial_test: false,
+            mooneye_test: false,
+            benchmark: false,
+            block_cache: false,
+            model: None,
+            time_limit: 120,
+            trace_file: None,
+            trace_ly: false,
+            trace_clocks: false,
+            break_at_instruction: None,
+            config_file: None,
+            record_file: None,
+            play_file: None,
+            input_script: None,
+            input_file: None,
         }
     }
 }
 
 fn print_help_and_exit() {
     println!("{} version v{}", env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION"));
+    println!("Usage: {} [options] [rom]", env!("CARGO_PKG_NAME"));
+    println!("       {} disasm [rom] --bank [n] --from [address] --to [address]", env!("CARGO_PKG_NAME"));
+    println!("       {} disasm [rom] --out [directory]", env!("CARGO_PKG_NAME"));
+    println!("       {} trace-diff [our trace] [reference trace] --context [n] --rerun [rom] [run options]", env!("CARGO_PKG_NAME"));
+    println!("Subcommand disasm: Disassemble part of a ROM bank to RGBDS syntax. The bank defaults to 0, and");
+    println!("           the range defaults to the whole bank. With --out, the whole ROM is disassembled by following");
+    println!("           the code from its entry points, into an RGBDS source tree that assembles back into the ROM.");
+    println!("Subcommand trace-diff: Compare a trace from -T with one from another emulator, and show the first");
+    println!("           instruction where they differ along with the n before it (10 by default). With --rerun,");
+    println!("           the ROM is run again headless and breaks into the debugger at that instruction. Give it");
+    println!("           the same -M, -c, --input, --input-file or --play options that the trace was made with.");
     println!("Option -d: Dump system memory to a log file upon termination.");
     println!("Option -b [address]: Break at the given PC address. Can be specified multiple times.");
     println!("Option -k [address]: Kill the program at the given PC address. Can only be specified once.");
     println!("Option -v: Enable verbose instruction execution output.");
+    println!("Option -s: Run a test ROM headless, passing or failing when its serial output says \"Passed\" or \"Failed\".");
+    println!("Option -m: Run Mooneye test ROMs headless, passing when LD B,B is hit with the Fibonacci numbers in B-L.");
+    println!("           The ROM path can also be a directory, which is searched for test ROMs. Movies and scripted");
+    println!("           input can't be used with it.");
+    println!("Option -p: Run the ROM headless as fast as possible for the -t time limit, and report the speed.");
+    println!("Option -c: Run cached basic blocks of decoded instructions, so each instruction is only decoded once.");
+    println!("           Every cycle still runs the rest of the system, so whole runs only gain around 10-15%.");
+    println!("Option -M [model]: Hardware model to emulate, one of DMG0, DMG, MGB, SGB, SGB2, CGB or AGB.");
+    println!("           Defaults to CGB for Game Boy Color games, and DMG for everything else.");
+    println!("Option -t [seconds]: Fail a headless test ROM after this much emulated time. Defaults to 120.");
+    println!("Option -T [file]: Write a gameboy-doctor style trace of every instruction to the file. With -m and a");
+    println!("           directory of ROMs, the file is a directory that gets a trace for each ROM.");
+    println!("Option --trace-ly: Add an LY column to the trace.");
+    println!("Option --trace-cycles: Add a column with the clocks run so far to the trace.");
+    println!("Option --config [file]: Read key and controller bindings from this config file, instead of gblite/config");
+    println!("           in the user's config directory.");
+    println!("Option --record [file]: Record the joypad state for every frame to a movie file.");
+    println!("Option --play [file]: Play back a movie recorded with --record, on the same ROM and model.");
+    println!("Option --input [presses]: Press buttons at set frames, like \"start@120,a@180:10\" to press Start on frame 120");
+    println!("           and A on frame 180 for 10 frames. Presses last 5 frames if no length is given.");
+    println!("Option --input-file [file]: Read the presses for --input from a file, one or more per line.");
+    println!("Controls: The arrow keys for the d-pad, X and Z for A and B, Enter for Start and Backspace for Select.");
+    println!("          Game controllers can be plugged in at any time, and use the d-pad or left stick, the bottom");
+    println!("          and right face buttons for A and B, and Back and Start for Select and Start. Escape quits,");
+    println!("          and F12 breaks into the debugger. The config file can change all of these.");
     std::process::exit(1);
 }
 
+// Parse a hex address argument, with or without a 0x or $ prefix.
+fn parse_addr(s: &str) -> Result<u16, String> {
+    let digits = s.trim_start_matches("0x").trim_start_matches('$');
+    u16::from_str_radix(digits, 16).map_err(|e| format!("Error parsing address \"{}\": {}", s, e))
+}
+
+// The disasm subcommand, returning the process exit status.
+fn disasm_main(args: &[String]) -> i32 {
+    let mut rom_file = None;
+    let (mut bank, mut from, mut to, mut out) = (0, None, None, None);
+    let mut i = 0;
+    while i < args.len() {
+        let value = args.get(i + 1).map(String::as_str).unwrap_or_default();
+        let parsed = match args[i].as_str() {
+            "--bank" => value.parse().map(|b| bank = b).map_err(|e| format!("Error parsing bank \"{}\": {}", value, e)),
+            "--from" => parse_addr(value).map(|a| from = Some(a)),
+            "--to"   => parse_addr(value).map(|a| to = Some(a)),
+            "--out" if value.is_empty() => Err("Missing the directory for --out".to_string()),
+            "--out"  => { out = Some(value.to_string()); Ok(()) },
+            other if !other.starts_with('-') => { rom_file = Some(other.to_string()); i += 1; continue; },
+            other => Err(format!("Read invalid argument, {}", other)),
+        };
+        if let Err(e) = parsed {
+            eprintln!("{}\n", e);
+            print_help_and_exit();
+        }
+        i += 2;
+    }
+
+    let rom_file = rom_file.unwrap_or_else(|| { print_help_and_exit(); unreachable!() });
+    let rom = match fs::read(&rom_file) {
+        Ok(rom) => rom,
+        Err(e) => {
+            eprintln!("Error reading file: {}", e);
+            return 1;
+        }
+    };
+
+    if let Some(out) = out {
+        return match disasm::write_source_tree(&rom, Path::new(&out)) {
+            Ok(summary) => {
+                println!("Wrote {} banks to {}, with {} bytes of code and {} labels", summary.banks, out, summary.code_bytes, summary.labels);
+                0
+            },
+            Err(e) => {
+                eprintln!("Error writing {}: {}", out, e);
+                1
+            }
+        };
+    }
+
+    let start = if bank == 0 { 0x0000 } else { 0x4000 };
+    match disasm::disassemble_bank(&rom, bank, from.unwrap_or(start), to.unwrap_or(start + 0x4000)) {
+        Ok(lines) => {
+            for line in lines { println!("{}", line); }
+            0
+        },
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            1
+        }
+    }
+}
+
+// The trace-diff subcommand, returning the process exit status: 0 when the traces match, and 1 when
+// they diverge or can't be read.
+//
+// The rerun takes the options that change how the ROM runs, so it can be run the same way as it was
+// for the trace.
+fn trace_diff_main(args: &[String]) -> i32 {
+    let mut files = Vec::new();
+    let (mut context, mut rerun) = (10, None);
+    let mut cfg = RuntimeConfig::new();
+    let mut i = 0;
+    while i < args.len() {
+        let value = args.get(i + 1).map(String::as_str).unwrap_or_default();
+        let parsed = match args[i].as_str() {
+            "--context" => value.parse().map(|n| context = n).map_err(|e| format!("Error parsing context \"{}\": {}", value, e)),
+            "--rerun" if value.is_empty() => Err("Missing the ROM for --rerun".to_string()),
+            "--rerun"   => { rerun = Some(value.to_string()); Ok(()) },
+            "-M" => model::Model::from_name(value).map(|m| cfg.model = Some(m)).ok_or(format!("Unknown hardware model \"{}\"", value)),
+            "-c" => { cfg.block_cache = true; i += 1; continue; },
+            "--input" | "--input-file" | "--play" if value.is_empty() => Err(format!("Missing the value for {}", args[i])),
+            "--input"      => { cfg.input_script = Some(value.to_string()); Ok(()) },
+            "--input-file" => { cfg.input_file = Some(value.to_string()); Ok(()) },
+            "--play"       => { cfg.play_file = Some(value.to_string()); Ok(()) },
+            other if !other.starts_with('-') => { files.push(other.to_string()); i += 1; continue; },
+            other => Err(format!("Read invalid argument, {}", other)),
+        };
+        if let Err(e) = parsed {
+            eprintln!("{}\n", e);
+            print_help_and_exit();
+        }
+        i += 2;
+    }
+    if files.len() != 2 { print_help_and_exit(); }
+
+    let (ours, reference) = match (trace::read_trace(&files[0]), trace::read_trace(&files[1])) {
+        (Ok(ours), Ok(reference)) => (ours, reference),
+        (Err(e), _) | (_, Err(e)) => {
+            eprintln!("Error reading trace: {}", e);
+            return 1;
+        }
+    };
+
+    let (ours_start, ref_start, ours_idx, ref_idx) = match trace::diff::compare(&ours, &reference) {
+        trace::diff::Comparison::Matching { ours_start, ref_start, lines } => {
+            println!("The traces match for {} instructions, from line {} of ours and line {} of the reference",
+                     lines, ours_start + 1, ref_start + 1);
+            return 0;
+        },
+        trace::diff::Comparison::Diverged { ours_start, ref_start, ours, reference } => (ours_start, ref_start, ours, reference),
+    };
+
+    println!("Lined up at line {} of ours and line {} of the reference", ours_start + 1, ref_start + 1);
+    println!("First difference after {} matching instructions, at line {} of ours and line {} of the reference:",
+             ours_idx - ours_start, ours_idx + 1, ref_idx + 1);
+    for state in &ours[ours_idx.saturating_sub(context).max(ours_start)..ours_idx] {
+        println!("      {}", trace::diff::describe(state));
+    }
+    println!("ours  {}", trace::diff::describe(&ours[ours_idx]));
+    println!("ref   {}", trace::diff::describe(&reference[ref_idx]));
+    println!("Differences: {}", trace::diff::differences(&ours[ours_idx], &reference[ref_idx]).join(", "));
+
+    // The state is from before each instruction runs, so the one before the difference caused it.
+    let culprit = if ours_idx > ours_start { ours_idx - 1 } else { ours_idx };
+    let rom_file = match rerun {
+        Some(rom_file) => rom_file,
+        None => return 1,
+    };
+    let rom = match fs::read(&rom_file) {
+        Ok(rom) => rom,
+        Err(e) => {
+            eprintln!("Error reading ROM \"{}\": {}", rom_file, e);
+            return 1;
+        }
+    };
+    cfg.headless = true;
+    cfg.break_at_instruction = Some(culprit as u64);
+    let frame_input = match setup_frame_input(&mut cfg, &rom) {
+        Ok(input) => input,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            return 1;
+        }
+    };
+
+    let running = Arc::new(AtomicBool::new(true));
+    let r = running.clone();
+    ctrlc::set_handler(move || {
+        r.store(false, Ordering::SeqCst);
+    }).expect("Error setting Ctrl-C handler");
+    runner::run_until_stopped(rom, &cfg, frame_input, &running)
+}
+
+// The bindings from the config file given with --config, or the default one if it exists.
+fn load_bindings(cfg: &RuntimeConfig) -> Result<config::Bindings, Vec<String>> {
+    match (&cfg.config_file, config::default_path()) {
+        (Some(file), _) => config::Bindings::load(Path::new(file)),
+        (None, Some(path)) if path.exists() => config::Bindings::load(&path),
+        (None, _) => Ok(config::Bindings::defaults()),
+    }
+}
+
+// Start recording or playing back a movie, or playing an input script, if one was asked for.
+// Playing a movie checks it was recorded on this ROM, and runs the model it was recorded on.
+fn setup_frame_input(cfg: &mut RuntimeConfig, rom: &[u8]) -> Result<Option<movie::FrameInput>, String> {
+    let sources = [&cfg.record_file, &cfg.play_file, &cfg.input_script, &cfg.input_file];
+    if sources.iter().filter(|source| source.is_some()).count() > 1 {
+        return Err("only one of --record, --play, --input and --input-file can be used at a time".to_string());
+    }
+
+    if let Some(presses) = &cfg.input_script {
+        return script::Script::parse(presses).map(|script| Some(movie::FrameInput::Script(script)))
+            .map_err(|e| format!("in --input: {}", e));
+    }
+    if let Some(file) = &cfg.input_file {
+        return script::Script::load(file).map(|script| Some(movie::FrameInput::Script(script)));
+    }
+
+    if let Some(file) = &cfg.play_file {
+        let movie = movie::read_movie(file)?;
+        movie.header.check_rom(rom).map_err(|e| format!("{}: {}", file, e))?;
+        match cfg.model {
+            Some(model) if model != movie.header.model => {
+                return Err(format!("{}: the movie was recorded on the {}, not the {}", file, movie.header.model, model));
+            },
+            _ => cfg.model = Some(movie.header.model),
+        }
+        println!("Playing back {} frames from {}.", movie.frames.len(), file);
+        return Ok(Some(movie::FrameInput::Play(movie::Player::new(movie))));
+    }
+
+    if let Some(file) = &cfg.record_file {
+        let model = cfg.model.unwrap_or_else(|| model::Model::for_rom(rom));
+        let recorder = movie::Recorder::create(file, &movie::Header::new(rom, model))
+            .map_err(|e| format!("creating movie file {}: {}", file, e))?;
+        return Ok(Some(movie::FrameInput::Record(recorder)));
+    }
+    Ok(None)
+}
+
 fn main() {
+    let args: Vec<String> = std::env::args().collect();
+    if args.get(1).map(String::as_str) == Some("disasm") {
+        std::process::exit(disasm_main(&args[2..]));
+    }
+    if args.get(1).map(String::as_str) == Some("trace-diff") {
+        std::process::exit(trace_diff_main(&args[2..]));
+    }
+
     let mut cfg: RuntimeConfig = RuntimeConfig::new();
     let mut arg_skip = 0;
     let mut arg_id = 1;
@@ -75,6 +367,73 @@ fn main() {
                     }
                 },
                 "-v" => { cfg.verbose  = true; },
+                "-s" => {
+                    cfg.headless = true;
+                    cfg.serial_test = true;
+                },
+                "-m" => {
+                    cfg.headless = true;
+                    cfg.mooneye_test = true;
+                },
+                "-p" => {
+                    cfg.headless = true;
+                    cfg.benchmark = true;
+                },
+                "-c" => { cfg.block_cache = true; },
+                "-M" => {
+                    arg_skip = 1;
+                    let name = std::env::args().nth(arg_id+1).unwrap_or_default();
+                    match model::Model::from_name(&name) {
+                        Some(model) => { cfg.model = Some(model); },
+                        None => {
+                            eprintln!("Unknown hardware model \"{}\"\n", name);
+                            print_help_and_exit();
+                        },
+                    }
+                },
+                "-t" => {
+                    arg_skip = 1;
+                    let secs_str = std::env::args().nth(arg_id+1).unwrap_or_default();
+                    match secs_str.parse::<u64>() {
+                        Ok(secs) => { cfg.time_limit = secs; },
+                        Err(e) => {
+                            eprintln!("Error parsing time limit argument \"{}\": {}\n", secs_str, e);
+                            print_help_and_exit();
+                        },
+                    }
+                },
+                "-T" => {
+                    arg_skip = 1;
+                    match std::env::args().nth(arg_id+1) {
+                        Some(file) => { cfg.trace_file = Some(file); },
+                        None => print_help_and_exit(),
+                    }
+                },
+                "--config" => {
+                    arg_skip = 1;
+                    match std::env::args().nth(arg_id+1) {
+                        Some(file) => { cfg.config_file = Some(file); },
+                        None => print_help_and_exit(),
+                    }
+                },
+                "--record" | "--play" => {
+                    arg_skip = 1;
+                    match std::env::args().nth(arg_id+1) {
+                        Some(file) if arg == "--record" => { cfg.record_file = Some(file); },
+                        Some(file) => { cfg.play_file = Some(file); },
+                        None => print_help_and_exit(),
+                    }
+                },
+                "--input" | "--input-file" => {
+                    arg_skip = 1;
+                    match std::env::args().nth(arg_id+1) {
+                        Some(input) if arg == "--input" => { cfg.input_script = Some(input); },
+                        Some(file) => { cfg.input_file = Some(file); },
+                        None => print_help_and_exit(),
+                    }
+                },
+                "--trace-ly" => { cfg.trace_ly = true; },
+                "--trace-cycles" => { cfg.trace_clocks = true; },
                 other => {
                     if &other[0..1] != "-" {
                         cfg.rom_file = Some(arg.clone());
@@ -89,7 +448,7 @@ fn main() {
         arg_id += 1;
     }
 
-    let fname = match &cfg.rom_file {
+    let fname = match cfg.rom_file.clone() {
         Some(f) => f,
         None => {
             print_help_and_exit();
@@ -99,7 +458,8 @@ fn main() {
 
     match fs::metadata(&fname) {
         Ok(meta) => {
-            if !meta.is_file() { print_help_and_exit(); }
+            let runnable = meta.is_file() || (cfg.mooneye_test && meta.is_dir());
+            if !runnable { print_help_and_exit(); }
         },
         Err(e) => {
             eprintln!("Error reading file: {}\n", e);
@@ -114,23 +474,79 @@ fn main() {
         r.store(false, Ordering::SeqCst);
     }).expect("Error setting Ctrl-C handler");
 
+    // Mooneye runs build a new system for each ROM they find. Movies and scripts are made for one
+    // ROM, so they can't be used with them.
+    if cfg.mooneye_test {
+        if [&cfg.record_file, &cfg.play_file, &cfg.input_script, &cfg.input_file].iter().any(|source| source.is_some()) {
+            eprintln!("Error: --record, --play, --input and --input-file can't be used with -m");
+            std::process::exit(1);
+        }
+        std::process::exit(runner::run_mooneye_suite(&fname, &cfg, &running));
+    }
+
     let mut mem = memory::Memory::new(0x10000);
     mem.load_rom_file(&fname);
+
+    // Movies play on the model they were recorded on, so they have to be set up before the CPU.
+    let frame_input = match setup_frame_input(&mut cfg, mem.rom()) {
+        Ok(input) => input,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+    };
     let mem = Arc::new(Mutex::new(mem));
 
-    let ppu = ppu::PPU::new(mem.clone());
+    // Headless runs have no keys or controllers to bind, so they don't read the config file.
+    let ppu = if cfg.headless {
+        ppu::PPU::headless(mem.clone())
+    } else {
+        match load_bindings(&cfg) {
+            Ok(bindings) => ppu::PPU::new(mem.clone(), bindings),
+            Err(errors) => {
+                eprintln!("Error in the config file:");
+                for e in errors { eprintln!("  {}", e); }
+                std::process::exit(1);
+            }
+        }
+    };
     let mut z80 = cpu::CPU::new(mem.clone(), ppu, &cfg);
+    let mut exit_code = 0;
+    if let Some(input) = frame_input {
+        z80.ppu.set_frame_input(input);
+    }
 
-    // Run instructions until the end of time
-    loop {
-        if !running.load(Ordering::SeqCst) {
-            println!("Received Ctrl+C signal, exiting!");
-            break;
+    if let Some(trace_file) = &cfg.trace_file {
+        match trace::TraceLog::create(trace_file, cfg.trace_ly, cfg.trace_clocks) {
+            Ok(trace) => z80.set_trace(trace),
+            Err(e) => {
+                eprintln!("Error creating trace file {}: {}", trace_file, e);
+                std::process::exit(1);
+            }
         }
+    }
 
-        if !z80.tick() { break; }
+    if cfg.serial_test {
+        let result = runner::run_serial_test(&mut z80, &running, cfg.time_limit);
+        println!("{}: {}", fname, result);
+        exit_code = result.exit_code();
+    } else if cfg.benchmark {
+        exit_code = runner::run_benchmark(&mut z80, &running, cfg.time_limit);
+    } else {
+        // Run instructions until the end of time
+        loop {
+            if !running.load(Ordering::SeqCst) {
+                println!("Received Ctrl+C signal, exiting!");
+                break;
+            }
+
+            if !z80.tick() { break; }
+        }
     }
 
+    z80.flush_trace();
+    z80.ppu.flush_frame_input();
+
     if cfg.dump_mem {
         let dt = Utc::now();
         let fname = format!("gblite_mem_{}_{:02}_{:02}_{}.log", dt.year(), dt.month(), dt.day(),
@@ -142,5 +558,9 @@ fn main() {
         }
     }
 
+    if cfg.headless {
+        std::process::exit(exit_code);
+    }
+
     thread::sleep(time::Duration::from_millis(100));
 }
@@ -3,10 +3,47 @@
 use std::fs;
 use std::io;
 
+use crate::interrupt::{Interrupt, IF_ADDR};
+use crate::serial::{Serial, SB_ADDR, SC_ADDR};
+use crate::timer::{Timer, DIV_ADDR, TAC_ADDR};
+use crate::joypad::{Button, Joypad};
+
+pub const P1_ADDR: u16   = 0xFF00; // P1/JOYP - Joypad select lines and button state
+pub const DMA_ADDR: u16  = 0xFF46; // DMA - Writing here starts an OAM DMA transfer from (val << 8)
+pub const KEY1_ADDR: u16 = 0xFF4D; // KEY1 - CGB speed switch, bit 7 is the current speed
+pub const BOOT_ADDR: u16 = 0xFF50; // Writing a non-zero value here unmaps the boot ROM
+
+const PPU_FIRST_ADDR: u16 = 0xFF40; // LCDC, the first of the PPU's registers
+const PPU_LAST_ADDR: u16  = 0xFF4F; // VBK, the last of them
+
+const OAM_ADDR: u16 = 0xFE00;
+const OAM_SIZE: u16 = 0xA0;
+
 pub struct Memory {
     mem:  Vec<u8>,
     rom:  Vec<u8>,
-    bios: Vec<u8>
+    bios: Vec<u8>,
+    dma:  Option<OamDma>,
+    serial: Serial,
+    timer: Timer,
+    joypad: Joypad,
+    ppu_regs_written: bool, // Set when the CPU writes a PPU register, until the PPU picks it up.
+    flat: Option<Vec<BusAccess>> // Set for the flat test bus, holding every CPU access made so far.
+}
+
+// A single CPU read or write on the flat test bus.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct BusAccess {
+    pub addr:  u16,
+    pub val:   u8,
+    pub write: bool
+}
+
+// An OAM DMA transfer copies one byte per machine cycle into OAM, after a one cycle startup delay.
+struct OamDma {
+    src:   u16,
+    pos:   u16,
+    delay: u8
 }
 
 pub enum MemClient {
@@ -19,32 +56,75 @@ impl Memory {
         Memory {
             mem:  vec![0; size],
             rom:  Vec::new(),
-            bios: Memory::default_bios()
+            bios: Memory::default_bios(),
+            dma:  None,
+            serial: Serial::new(),
+            timer: Timer::new(),
+            joypad: Joypad::new(),
+            ppu_regs_written: true,
+            flat: None
+        }
+    }
+
+    // Create a flat 64K bus for testing the CPU in isolation. Every address is plain RAM, with no
+    // boot ROM, cartridge or IO registers mapped in. Only the CPU is connected to it, so the PPU
+    // reads zeros (which keeps the LCD off) and its writes are dropped.
+    pub fn new_flat() -> Memory {
+        let mut mem = Memory::new(0x10000);
+        mem.flat = Some(Vec::new());
+        mem
+    }
+
+    // Take the CPU accesses made on the flat test bus since the last call.
+    pub fn take_bus_log(&mut self) -> Vec<BusAccess> {
+        match &mut self.flat {
+            Some(log) => std::mem::take(log),
+            None => Vec::new(),
         }
     }
 
     // TODO: Implement ROM switching and interfaces for different memory bank controllers.
-    pub fn get(&self, addr: u16, _client: MemClient) -> u8 {
+    pub fn get(&self, addr: u16, client: MemClient) -> u8 {
         let a = addr as usize;
-        if a < 0x100 {
+        if self.flat.is_some() {
+            match client {
+                MemClient::CPU => self.mem[a],
+                MemClient::PPU => 0x00,
+            }
+        } else if a < 0x100 {
             if self.bootrom_enabled() {
                 self.bios[a]
             } else {
-                self.rom[a]
+                self.rom.get(a).copied().unwrap_or(0xFF)
             }
-        } else if a < 0x4000 {
-            self.rom[a]
         } else if a < 0x8000 {
-            println!("Reading from ROM bank N, this is unimplemented!");
-            self.rom[a]
+            // Without a memory bank controller, bank N is always the second 16K of the ROM.
+            self.rom.get(a).copied().unwrap_or(0xFF)
+        } else if addr == P1_ADDR {
+            self.joypad.read()
+        } else if addr == SC_ADDR {
+            self.mem[a] | 0x7E
+        } else if (DIV_ADDR..=TAC_ADDR).contains(&addr) {
+            self.timer.read(addr)
+        } else if addr == IF_ADDR {
+            self.mem[a] | 0xE0 // The upper three bits of IF are unused, and always read as 1.
+        } else if addr == KEY1_ADDR {
+            self.mem[a] | 0x7E
         } else {
             self.mem[a]
         }
     }
 
-    pub fn set(&mut self, val: u8, addr: u16, _client: MemClient) {
+    pub fn set(&mut self, val: u8, addr: u16, client: MemClient) {
         let a = addr as usize;
-        if a < 0x100 {
+        if let MemClient::CPU = client {
+            if (PPU_FIRST_ADDR..=PPU_LAST_ADDR).contains(&addr) { self.ppu_regs_written = true; }
+        }
+        if self.flat.is_some() {
+            if let MemClient::CPU = client {
+                self.mem[a] = val;
+            }
+        } else if a < 0x100 {
             if self.bootrom_enabled() {
                 self.bios[a] = val;
             } else {
@@ -55,11 +135,172 @@ impl Memory {
         } else if a < 0x8000 {
             println!("Writing to ROM bank N, this is unimplemented!");
             self.rom[a] = val;
+        } else if addr == KEY1_ADDR {
+            // Only the prepare-switch bit is writable, the current speed is set by STOP.
+            self.mem[a] = (self.mem[a] & 0x80) | (val & 0x01);
+        } else if addr == P1_ADDR {
+            if self.joypad.write(val) { self.request_interrupt(Interrupt::Joypad); }
+        } else if addr == SC_ADDR {
+            self.mem[a] = val;
+            self.serial.write_control(val, self.mem[SB_ADDR as usize]);
+        } else if (DIV_ADDR..=TAC_ADDR).contains(&addr) {
+            self.timer.write(addr, val);
         } else {
             self.mem[a] = val;
         }
     }
 
+    // Write bytes for the debugger. Unlike the CPU's writes, writes to the cartridge area change
+    // the loaded ROM itself, so code there can be patched. Nothing is written unless all of the
+    // bytes land in memory that's there.
+    pub fn patch(&mut self, addr: u16, bytes: &[u8]) -> Result<(), String> {
+        let end = addr as usize + bytes.len();
+        if end > 0x10000 {
+            return Err(format!("the patch runs past the end of memory at 0x{:04x}", addr));
+        }
+        for a in addr as usize..end {
+            let mapped = self.flat.is_some() || a >= 0x8000 || a < self.rom.len() || (a < 0x100 && self.bootrom_enabled());
+            if !mapped {
+                return Err(format!("0x{:04x} is past the end of the {} byte ROM", a, self.rom.len()));
+            }
+        }
+
+        for (i, byte) in bytes.iter().enumerate() {
+            let a = addr as usize + i;
+            match a {
+                _ if self.flat.is_some() => self.mem[a] = *byte,
+                0x0000..=0x00FF if self.bootrom_enabled() => self.bios[a] = *byte,
+                0x0000..=0x7FFF => self.rom[a] = *byte,
+                _ => self.set(*byte, a as u16, MemClient::CPU),
+            }
+        }
+        Ok(())
+    }
+
+    // Read a byte for a CPU instruction. While OAM DMA is running it owns the bus, so the CPU can
+    // only see the IO registers and HRAM.
+    pub fn read_bus(&mut self, addr: u16) -> u8 {
+        if let Some(log) = &mut self.flat {
+            let val = self.mem[addr as usize];
+            log.push(BusAccess { addr, val, write: false });
+            val
+        } else if self.dma_blocks(addr) {
+            0xFF
+        } else {
+            self.get(addr, MemClient::CPU)
+        }
+    }
+
+    // Write a byte for a CPU instruction, which can also kick off OAM DMA. Writes to the ROM area go
+    // to the cartridge's memory bank controller, which isn't implemented, so they're dropped.
+    pub fn write_bus(&mut self, val: u8, addr: u16) {
+        if let Some(log) = &mut self.flat {
+            self.mem[addr as usize] = val;
+            log.push(BusAccess { addr, val, write: true });
+            return;
+        }
+
+        if self.dma_blocks(addr) || addr < 0x8000 {
+            return;
+        }
+
+        self.set(val, addr, MemClient::CPU);
+        if addr == DMA_ADDR {
+            self.dma = Some(OamDma { src: (val as u16) << 8, pos: 0, delay: 1 });
+        }
+    }
+
+    fn dma_blocks(&self, addr: u16) -> bool {
+        match &self.dma {
+            Some(dma) => dma.delay == 0 && addr < 0xFF00,
+            None => false,
+        }
+    }
+
+    // Advance the memory-side peripherals by one machine cycle.
+    pub fn tick(&mut self) {
+        self.tick_dma();
+
+        if self.serial.tick() {
+            self.mem[SB_ADDR as usize] = 0xFF;
+            self.mem[SC_ADDR as usize] &= 0x7F;
+            self.request_interrupt(Interrupt::Serial);
+        }
+        if self.timer.tick() {
+            self.request_interrupt(Interrupt::Timer);
+        }
+    }
+
+    fn tick_dma(&mut self) {
+        let (src, pos) = match &mut self.dma {
+            Some(dma) if dma.delay > 0 => { dma.delay -= 1; return; },
+            Some(dma) => (dma.src, dma.pos),
+            None => return,
+        };
+
+        let val = self.get(src + pos, MemClient::PPU);
+        self.mem[(OAM_ADDR + pos) as usize] = val;
+        if pos + 1 == OAM_SIZE {
+            self.dma = None;
+        } else if let Some(dma) = &mut self.dma {
+            dma.pos += 1;
+        }
+    }
+
+    // Whether the CPU has written any PPU registers since the last call. The PPU only has to read
+    // its registers back from memory when they've been written.
+    pub fn take_ppu_regs_written(&mut self) -> bool {
+        std::mem::take(&mut self.ppu_regs_written)
+    }
+
+    // Press or release a joypad button.
+    pub fn set_button(&mut self, button: Button, pressed: bool) {
+        if self.joypad.set_button(button, pressed) {
+            self.request_interrupt(Interrupt::Joypad);
+        }
+    }
+
+    // Set the whole joypad state at once, from a mask of the held buttons.
+    pub fn set_buttons(&mut self, pressed: u8) {
+        for button in Button::ALL.iter() {
+            self.set_button(*button, pressed & button.mask() != 0);
+        }
+    }
+
+    // Set DIV to the value the boot ROM leaves it at. Writing DIV normally resets it.
+    pub fn set_divider(&mut self, div: u8) {
+        self.timer.set_divider(div);
+    }
+
+    // Record the new CPU speed in KEY1 after a speed switch, which also clears the prepare bit.
+    pub fn set_double_speed(&mut self, enabled: bool) {
+        self.mem[KEY1_ADDR as usize] = if enabled { 0x80 } else { 0x00 };
+    }
+
+    // The CGB flag in the cartridge header at 0x143 has bit 7 set for CGB enhanced and CGB only games.
+    pub fn is_cgb_rom(&self) -> bool {
+        self.rom.get(0x143).is_some_and(|flag| (flag & 0x80) != 0)
+    }
+
+    // The whole cartridge ROM, for reading the header.
+    pub fn rom(&self) -> &[u8] {
+        &self.rom
+    }
+
+    // Take the bytes sent through the serial port since the last call.
+    pub fn take_serial_output(&mut self) -> Vec<u8> {
+        self.serial.take_output()
+    }
+
+    // Request an interrupt by setting its bit in the IF register.
+    pub fn request_interrupt(&mut self, ir: Interrupt) {
+        self.mem[IF_ADDR as usize] |= ir.mask();
+    }
+
+    pub fn load_rom(&mut self, rom: Vec<u8>) {
+        self.rom = rom;
+    }
+
     pub fn load_rom_file(&mut self, file_name : &str) {
         self.rom = fs::read(file_name).unwrap_or(vec![])
     }
@@ -80,7 +321,7 @@ impl Memory {
     }
 
     fn bootrom_enabled(&self) -> bool {
-        self.mem[0xFF50] == 0
+        self.mem[BOOT_ADDR as usize] == 0
     }
 
     // For debug use only: do a hex dump of the contents of our ROM cartridge.
@@ -0,0 +1,133 @@
+// Game Boy hardware models, and the state each one's boot ROM leaves the system in when it hands
+// over to the cartridge. Games tell the models apart by these values, most commonly checking for a
+// CGB by A being 0x11, and then for an AGB by bit 0 of B.
+
+use std::fmt::{Display, Formatter, Result};
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Model {
+    Dmg0, // The original Game Boy, with the early boot ROM.
+    Dmg,
+    Mgb,  // Game Boy Pocket and Light.
+    Sgb,
+    Sgb2,
+    Cgb,
+    Agb,  // Game Boy Advance, running Game Boy Color games.
+}
+
+// CPU and I/O register values right after the boot ROM. SP is always 0xFFFE and PC 0x0100.
+pub struct PowerOnState {
+    pub af: u16,
+    pub bc: u16,
+    pub de: u16,
+    pub hl: u16,
+    pub io: Vec<(u16, u8)>,
+}
+
+// The I/O registers that start out the same on every model: the serial data, timer, IF, sound,
+// LCD and scroll registers, and IE. LY isn't set here, the PPU starts at the top of the frame.
+const COMMON_IO: [(u16, u8); 35] = [
+    (0xFF00, 0xCF), (0xFF01, 0x00), (0xFF05, 0x00), (0xFF06, 0x00), (0xFF07, 0xF8), (0xFF0F, 0xE1),
+    (0xFF10, 0x80), (0xFF11, 0xBF), (0xFF12, 0xF3), (0xFF13, 0xFF), (0xFF14, 0xBF),
+    (0xFF16, 0x3F), (0xFF17, 0x00), (0xFF18, 0xFF), (0xFF19, 0xBF),
+    (0xFF1A, 0x7F), (0xFF1B, 0xFF), (0xFF1C, 0x9F), (0xFF1D, 0xFF), (0xFF1E, 0xBF),
+    (0xFF20, 0xFF), (0xFF21, 0x00), (0xFF22, 0x00), (0xFF23, 0xBF), (0xFF24, 0x77), (0xFF25, 0xF3),
+    (0xFF40, 0x91), (0xFF42, 0x00), (0xFF43, 0x00), (0xFF45, 0x00), (0xFF47, 0xFC),
+    (0xFF4A, 0x00), (0xFF4B, 0x00), (0xFF55, 0xFF), (0xFFFF, 0x00),
+];
+
+// VBK, HDMA1-4, RP and SVBK. These only exist on the CGB and AGB, other models read them as 0xFF.
+const CGB_IO: [(u16, u8); 7] = [
+    (0xFF4F, 0xFE), (0xFF51, 0xFF), (0xFF52, 0xFF), (0xFF53, 0xFF), (0xFF54, 0xFF), (0xFF56, 0x3E),
+    (0xFF70, 0xF8),
+];
+
+impl Model {
+    const ALL: [Model; 7] = [Model::Dmg0, Model::Dmg, Model::Mgb, Model::Sgb, Model::Sgb2, Model::Cgb, Model::Agb];
+
+    // Parse a model name like "dmg" or "CGB".
+    pub fn from_name(name: &str) -> Option<Model> {
+        Model::ALL.iter().copied().find(|m| m.to_string().eq_ignore_ascii_case(name))
+    }
+
+    // The model to run a ROM on when none is picked: CGB games on a CGB, and everything else on a DMG.
+    pub fn for_rom(rom: &[u8]) -> Model {
+        if rom.get(0x143).is_some_and(|flag| (flag & 0x80) != 0) { Model::Cgb } else { Model::Dmg }
+    }
+
+    pub fn is_cgb(self) -> bool {
+        matches!(self, Model::Cgb | Model::Agb)
+    }
+
+    // The state after the boot ROM for a cartridge with the given ROM. The DMG and MGB set H and C
+    // from the header checksum, and the CGB and AGB set up B, H and L from the header too when
+    // they run a game that doesn't support CGB features.
+    pub fn power_on_state(self, rom: &[u8]) -> PowerOnState {
+        let byte = |addr: usize| rom.get(addr).copied().unwrap_or(0);
+        let checksum_flags = if byte(0x14D) != 0 { 0xB0 } else { 0x80 };
+        let cgb_rom = (byte(0x143) & 0x80) != 0;
+
+        let (af, bc, de, hl) = match self {
+            Model::Dmg0 => (0x0100, 0xFF13, 0x00C1, 0x8403),
+            Model::Dmg  => (0x0100 | checksum_flags, 0x0013, 0x00D8, 0x014D),
+            Model::Mgb  => (0xFF00 | checksum_flags, 0x0013, 0x00D8, 0x014D),
+            Model::Sgb  => (0x0100, 0x0014, 0x0000, 0xC060),
+            Model::Sgb2 => (0xFF00, 0x0014, 0x0000, 0xC060),
+            Model::Cgb if cgb_rom => (0x1180, 0x0000, 0xFF56, 0x000D),
+            Model::Agb if cgb_rom => (0x1100, 0x0100, 0xFF56, 0x000D),
+            Model::Cgb | Model::Agb => {
+                // For Nintendo's own games, the boot ROM hashes the title to pick a palette.
+                let nintendo = byte(0x14B) == 0x01 || (byte(0x14B) == 0x33 && rom.get(0x144..0x146) == Some(b"01"));
+                let (b, hl) = if nintendo {
+                    ((0x134..0x144).fold(0u8, |sum, addr| sum.wrapping_add(byte(addr))), 0x991A)
+                } else {
+                    (0x00, 0x007C)
+                };
+
+                // The AGB boot ROM finishes with an INC B, which leaves its mark on the flags.
+                if self == Model::Agb {
+                    let b = b.wrapping_add(1);
+                    let f = (if b == 0 { 0x80 } else { 0x00 }) | (if b & 0xF == 0 { 0x20 } else { 0x00 });
+                    (0x1100 | f, (b as u16) << 8, 0x0008, hl)
+                } else {
+                    (0x1180, (b as u16) << 8, 0x0008, hl)
+                }
+            },
+        };
+
+        // The rest of the I/O registers that differ: SC, DIV, NR52, STAT and DMA. DIV depends on how
+        // long the boot ROM took, which isn't known for the SGB and CGB models, so it's left at 0.
+        let (sc, div, nr52, stat, dma) = match self {
+            Model::Dmg0 => (0x7E, Some(0x18), 0xF1, 0x81, 0xFF),
+            Model::Dmg | Model::Mgb => (0x7E, Some(0xAB), 0xF1, 0x85, 0xFF),
+            Model::Sgb | Model::Sgb2 => (0x7E, None, 0xF0, 0x85, 0xFF),
+            Model::Cgb | Model::Agb => (0x7F, None, 0xF1, 0x85, 0x00),
+        };
+
+        let mut io = COMMON_IO.to_vec();
+        io.extend([(0xFF02, sc), (0xFF26, nr52), (0xFF41, stat), (0xFF46, dma)]);
+        if let Some(div) = div { io.push((0xFF04, div)); }
+        if self.is_cgb() {
+            io.extend(CGB_IO);
+        } else {
+            io.extend(CGB_IO.iter().map(|(addr, _)| (*addr, 0xFF)));
+        }
+
+        PowerOnState { af, bc, de, hl, io }
+    }
+}
+
+impl Display for Model {
+    fn fmt(&self, f: &mut Formatter) -> Result {
+        let name = match *self {
+            Model::Dmg0 => "DMG0",
+            Model::Dmg  => "DMG",
+            Model::Mgb  => "MGB",
+            Model::Sgb  => "SGB",
+            Model::Sgb2 => "SGB2",
+            Model::Cgb  => "CGB",
+            Model::Agb  => "AGB",
+        };
+        write!(f, "{}", name)
+    }
+}
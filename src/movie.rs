@@ -0,0 +1,256 @@
+// Input movies, for reproducing a run exactly. A movie records the joypad state for every frame,
+// along with a header saying which ROM and model it was made on:
+//
+//   gblite movie
+//   rom 8A3D7C21 TETRIS
+//   model DMG
+//   start power-on
+//   frames
+//   ........
+//   .......S
+//
+// Each frame line has a letter for every held button, in the order RLUDABsS (s is Select), and a
+// dot for the others. A frame starts when the PPU enters VBlank, and the joypad only changes then
+// while a movie is recording or playing, so the game sees the same input at the same point every
+// time. That relies on the emulator running the same way from power-on every time.
+//
+// Movies can only start from power-on for now. There's room in the header for starting from an
+// embedded saved state, but without save states those movies are rejected.
+
+use std::fmt::{self, Display, Formatter};
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::str::FromStr;
+
+use crate::joypad::Button;
+use crate::model::Model;
+use crate::script::Script;
+
+const MAGIC: &str = "gblite movie";
+const BUTTON_LETTERS: &[u8; 8] = b"RLUDABsS";
+
+// The CRC-32 of a whole ROM, the same checksum ROM databases use.
+pub fn rom_checksum(rom: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for byte in rom {
+        crc ^= *byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 { (crc >> 1) ^ 0xEDB8_8320 } else { crc >> 1 };
+        }
+    }
+    !crc
+}
+
+fn frame_line(state: u8) -> String {
+    Button::ALL.iter().zip(BUTTON_LETTERS.iter())
+        .map(|(button, letter)| if state & button.mask() != 0 { *letter as char } else { '.' })
+        .collect()
+}
+
+fn parse_frame(line: &str) -> Option<u8> {
+    if line.len() != BUTTON_LETTERS.len() { return None; }
+    let mut state = 0;
+    for ((c, letter), button) in line.bytes().zip(BUTTON_LETTERS.iter()).zip(Button::ALL.iter()) {
+        match c {
+            b'.' => (),
+            _ if c == *letter => state |= button.mask(),
+            _ => return None,
+        }
+    }
+    Some(state)
+}
+
+// Which ROM and model a movie was made on.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Header {
+    pub checksum: u32,
+    pub title: String, // Only for people reading the file, the checksum is what's checked.
+    pub model: Model,
+}
+
+impl Header {
+    pub fn new(rom: &[u8], model: Model) -> Header {
+        let title = rom.get(0x134..0x144).unwrap_or(&[]).iter()
+            .take_while(|b| **b != 0).map(|b| *b as char).filter(|c| c.is_ascii_graphic() || *c == ' ')
+            .collect::<String>();
+        Header { checksum: rom_checksum(rom), title: title.trim().to_string(), model }
+    }
+
+    // Make sure a movie is being played back on the ROM it was recorded on.
+    pub fn check_rom(&self, rom: &[u8]) -> Result<(), String> {
+        let checksum = rom_checksum(rom);
+        if checksum != self.checksum {
+            let title = if self.title.is_empty() { String::new() } else { format!(" ({})", self.title) };
+            return Err(format!("the movie was recorded on a ROM with checksum {:08X}{}, but this ROM's is {:08X}",
+                               self.checksum, title, checksum));
+        }
+        Ok(())
+    }
+}
+
+impl Display for Header {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        writeln!(f, "{}", MAGIC)?;
+        writeln!(f, "{}", format!("rom {:08X} {}", self.checksum, self.title).trim_end())?;
+        writeln!(f, "model {}", self.model)?;
+        writeln!(f, "start power-on")?;
+        writeln!(f, "frames")
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Movie {
+    pub header: Header,
+    pub frames: Vec<u8>, // The held buttons for each frame, as joypad masks.
+}
+
+impl FromStr for Movie {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Movie, String> {
+        let mut lines = text.lines().enumerate().map(|(i, line)| (i + 1, line.trim()));
+        if lines.next().map(|(_, line)| line) != Some(MAGIC) {
+            return Err(format!("not a movie, the first line should be \"{}\"", MAGIC));
+        }
+
+        let (mut rom, mut model, mut start) = (None, None, None);
+        for (n, line) in &mut lines {
+            let (key, value) = line.split_once(' ').unwrap_or((line, ""));
+            match key {
+                "rom" => {
+                    let (checksum, title) = value.split_once(' ').unwrap_or((value, ""));
+                    let checksum = u32::from_str_radix(checksum, 16)
+                        .map_err(|_| format!("line {}: bad ROM checksum \"{}\"", n, checksum))?;
+                    rom = Some((checksum, title.to_string()));
+                },
+                "model" => {
+                    model = Some(Model::from_name(value).ok_or_else(|| format!("line {}: unknown model \"{}\"", n, value))?);
+                },
+                "start" if value == "power-on" => start = Some(n),
+                "start" => {
+                    return Err(format!("line {}: the movie starts from \"{}\", only power-on is supported", n, value));
+                },
+                "frames" => break,
+                _ => return Err(format!("line {}: unknown header line \"{}\"", n, line)),
+            }
+        }
+
+        let (checksum, title) = rom.ok_or("the header is missing the rom line")?;
+        let model = model.ok_or("the header is missing the model line")?;
+        start.ok_or("the header is missing the start line")?;
+
+        let mut frames = Vec::new();
+        for (n, line) in lines.filter(|(_, line)| !line.is_empty()) {
+            frames.push(parse_frame(line).ok_or_else(|| format!("line {}: bad frame \"{}\", expected something like ....A..S", n, line))?);
+        }
+        Ok(Movie { header: Header { checksum, title, model }, frames })
+    }
+}
+
+pub fn read_movie(path: &str) -> Result<Movie, String> {
+    let text = fs::read_to_string(path).map_err(|e| format!("{}: {}", path, e))?;
+    text.parse().map_err(|e| format!("{}: {}", path, e))
+}
+
+// A movie being recorded. Frames are written as they happen, so a crash still leaves a movie of
+// the run up to it.
+pub struct Recorder {
+    out: BufWriter<File>,
+    held: u8,
+}
+
+impl Recorder {
+    pub fn create(path: &str, header: &Header) -> io::Result<Recorder> {
+        let mut out = BufWriter::new(File::create(path)?);
+        write!(out, "{}", header)?;
+        Ok(Recorder { out, held: 0 })
+    }
+}
+
+pub struct Player {
+    movie: Movie,
+    frame: usize,
+}
+
+impl Player {
+    pub fn new(movie: Movie) -> Player {
+        Player { movie, frame: 0 }
+    }
+}
+
+// Input that's latched into the joypad once a frame, instead of following the window directly.
+pub enum FrameInput {
+    Record(Recorder),
+    Play(Player),
+    Script(Script),
+}
+
+impl FrameInput {
+    // Buttons pressed or released in the window. A recording keeps them for the next frame, and
+    // they're ignored while a movie or script plays.
+    pub fn window_buttons(&mut self, buttons: &[(Button, bool)]) {
+        if let FrameInput::Record(recorder) = self {
+            for (button, pressed) in buttons {
+                if *pressed { recorder.held |= button.mask(); } else { recorder.held &= !button.mask(); }
+            }
+        }
+    }
+
+    // The joypad state for the frame that's starting, or None once there's no input left and the
+    // window should take over again.
+    pub fn next_frame(&mut self) -> Option<u8> {
+        match self {
+            FrameInput::Record(recorder) => {
+                if let Err(e) = writeln!(recorder.out, "{}", frame_line(recorder.held)) {
+                    eprintln!("Error writing movie, recording stopped: {}", e);
+                    return None;
+                }
+                Some(recorder.held)
+            },
+            FrameInput::Play(player) => {
+                let state = player.movie.frames.get(player.frame).copied();
+                match state {
+                    Some(_) => player.frame += 1,
+                    None => println!("Movie finished after {} frames.", player.frame),
+                }
+                state
+            },
+            FrameInput::Script(script) => script.next_frame(),
+        }
+    }
+
+    pub fn flush(&mut self) -> io::Result<()> {
+        match self {
+            FrameInput::Record(recorder) => recorder.out.flush(),
+            FrameInput::Play(_) | FrameInput::Script(_) => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn movies_round_trip_and_check_the_rom() {
+        assert_eq!(rom_checksum(b"123456789"), 0xCBF43926);
+
+        let mut rom = vec![0; 0x150];
+        rom[0x134..0x13A].copy_from_slice(b"TETRIS");
+        let header = Header::new(&rom, Model::Dmg);
+        assert_eq!(header.title, "TETRIS");
+
+        let text = format!("{}........\n....A..S\nR.U.....\n", header);
+        let movie: Movie = text.parse().unwrap();
+        assert_eq!(movie.header, header);
+        assert_eq!(movie.frames, vec![0x00, Button::A.mask() | Button::Start.mask(), Button::Right.mask() | Button::Up.mask()]);
+        assert_eq!(frame_line(movie.frames[1]), "....A..S");
+
+        assert!(movie.header.check_rom(&rom).is_ok());
+        rom[0x100] = 0xC3;
+        assert!(movie.header.check_rom(&rom).unwrap_err().contains("(TETRIS)"));
+
+        let state_start = text.replace("start power-on", "start state");
+        assert_eq!(state_start.parse::<Movie>().err(), Some("line 4: the movie starts from \"state\", only power-on is supported".to_string()));
+    }
+}
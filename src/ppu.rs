@@ -5,11 +5,15 @@ use crate::util;
 use crate::memory::Memory;
 use crate::memory::MemClient;
 use crate::window::Window;
+use crate::config::Bindings;
+use crate::movie::FrameInput;
+use crate::interrupt::Interrupt;
 
 use std::fmt::{Display, Formatter, Result};
 use std::sync::Arc;
 use std::sync::Mutex;
-use std::time::Instant;
+use std::thread;
+use std::time::{Duration, Instant};
 
 #[derive(Copy, Clone, PartialEq)]
 enum PPUState {
@@ -93,13 +97,16 @@ struct PPUDebug {
 }
 
 pub struct PPU {
-    lcd: Window,             // The actual graphics window, not to be confused with a Game Boy window map/tile.
+    lcd: Option<Window>,     // The actual graphics window, not to be confused with a Game Boy window map/tile.
     mem: Arc<Mutex<Memory>>, // Reference to our Memory object.
     pixels: Vec<u8>,         // Vector containing pixel data. Currently UINT RGB8 format.
     cfg: PPUConfig,          // Struct containing all PPU register config values
     dbg: PPUDebug,           // Struct containing debug information and statistics
     lclk: u32,               // The machine cycle for this line, from [0, 113].
+    stat_line: bool,         // The OR of all enabled STAT interrupt sources, requests fire on its rising edge.
+    pushed_status: (u8, u8), // LY and STAT as they were last written to memory.
     alive: bool,             // Whether or not the application should continue running. This is != LCD disabled.
+    frame_input: Option<FrameInput>, // A movie or script setting the joypad each frame, instead of the window.
 }
 
 impl PPU {
@@ -107,9 +114,20 @@ impl PPU {
     const WIDTH:  usize = 160;
     const HEIGHT: usize = 144;
 
-    pub fn new(mem: Arc<Mutex<Memory>>) -> Self {
-        let lcd = Window::new(PPU::WIDTH, PPU::HEIGHT);
+    // A frame is 70224 clocks of the 4 MiHz system clock.
+    const FRAME_TIME: Duration = Duration::from_micros(16_743);
 
+    pub fn new(mem: Arc<Mutex<Memory>>, bindings: Bindings) -> Self {
+        PPU::with_window(mem, Some(Window::new(PPU::WIDTH, PPU::HEIGHT, bindings)))
+    }
+
+    // Create a PPU without a window, for running the emulator without SDL. Frames are still
+    // rendered into the pixel buffer, they just aren't presented anywhere.
+    pub fn headless(mem: Arc<Mutex<Memory>>) -> Self {
+        PPU::with_window(mem, None)
+    }
+
+    fn with_window(mem: Arc<Mutex<Memory>>, lcd: Option<Window>) -> Self {
         let regs: Vec<PPUReg> = [
             PPUReg::Lcdc,
             PPUReg::Stat,
@@ -157,7 +175,7 @@ impl PPU {
         };
 
         let dbg = PPUDebug {
-            enabled: true,
+            enabled: lcd.is_some(),
             last_frame: Instant::now(),
         };
 
@@ -168,11 +186,14 @@ impl PPU {
             cfg: cfg,
             dbg: dbg,
             lclk: 0,
+            stat_line: false,
+            pushed_status: (0, 0),
             alive: true,
+            frame_input: None,
         };
 
         // Initialize PPU config registers
-        ppu.push_registers();
+        ppu.push_registers(true);
 
         ppu
     }
@@ -183,7 +204,7 @@ impl PPU {
 
         /*
          * PPU clock cycle overview
-         * 1. Pull PPU CFG registers and modify settings accordingly
+         * 1. Pull PPU CFG registers if the CPU wrote any, and modify settings accordingly
          * 2. Check for window events
          * 3. Determine the current PPUState
          * 4. Do the appropriate work for this state
@@ -191,12 +212,13 @@ impl PPU {
          */
 
         // Check window events and for register changes
-        self.pull_registers();
+        let pulled = self.pull_registers();
         self.check_events();
 
         if !self.alive { return; }
 
         if self.cfg.lcd_enabled {
+            let prev_state = self.cfg.state;
             match self.cfg.state {
                 PPUState::HBlank => {
                     if self.lclk == 63 {
@@ -243,9 +265,30 @@ impl PPU {
                     self.lclk += 1;
                 }
             }
+
+            if prev_state != PPUState::VBlank && self.cfg.state == PPUState::VBlank {
+                self.latch_frame_input();
+                self.request_interrupt(Interrupt::VBlank);
+            }
+            self.update_stat_interrupt();
         }
 
-        self.push_registers();
+        self.push_registers(pulled);
+    }
+
+    // The STAT interrupt is requested when any of its enabled sources becomes active. Sources that
+    // stay active block further requests until they all go low again ("STAT blocking").
+    fn update_stat_interrupt(&mut self) {
+        self.cfg.ly_eq_lyc = self.cfg.ly == self.cfg.lyc;
+        let line = (self.cfg.ly_eq_lyc_intr && self.cfg.ly_eq_lyc) ||
+                   (self.cfg.oam_intr && self.cfg.state == PPUState::OAMSearch) ||
+                   (self.cfg.vblank_intr && self.cfg.state == PPUState::VBlank) ||
+                   (self.cfg.hblank_intr && self.cfg.state == PPUState::HBlank);
+
+        if line && !self.stat_line {
+            self.request_interrupt(Interrupt::LcdStat);
+        }
+        self.stat_line = line;
     }
 
     fn render_line(&mut self) {
@@ -328,7 +371,9 @@ impl PPU {
     }
 
     fn present(&mut self) {
-        self.lcd.draw(self.pixels.as_slice());
+        if let Some(lcd) = &mut self.lcd {
+            lcd.draw(self.pixels.as_slice());
+        }
 
         if self.dbg.enabled {
             let now = Instant::now();
@@ -346,6 +391,71 @@ impl PPU {
         self.alive
     }
 
+    // Whether the debug hotkey was pressed in the window since the last call.
+    pub fn take_debug_request(&mut self) -> bool {
+        self.lcd.as_mut().is_some_and(|lcd| lcd.take_debug_request())
+    }
+
+    // Service window events without advancing the LCD, used while the system clock is stopped.
+    // There are no VBlanks to latch the frame input on then, so each call counts as a frame instead,
+    // which lets a movie or script press the button that wakes the CPU up. With a window each call
+    // waits for a frame's time, so recordings don't fill up with frames while stopped.
+    pub fn idle(&mut self) {
+        if !self.is_alive() {
+            return;
+        }
+
+        if self.lcd.is_some() {
+            thread::sleep(PPU::FRAME_TIME);
+        }
+        self.poll_window();
+        if self.lcd.as_ref().is_some_and(|lcd| !lcd.is_open()) {
+            self.terminate();
+        }
+        self.latch_frame_input();
+    }
+
+    // Whether a movie or script is still playing, and so could press a button later on.
+    pub fn is_playing_input(&self) -> bool {
+        matches!(self.frame_input, Some(FrameInput::Play(_)) | Some(FrameInput::Script(_)))
+    }
+
+    // Take the joypad state from a movie or script once a frame, from now on.
+    pub fn set_frame_input(&mut self, input: FrameInput) {
+        self.frame_input = Some(input);
+    }
+
+    pub fn flush_frame_input(&mut self) {
+        if let Some(Err(e)) = self.frame_input.as_mut().map(FrameInput::flush) {
+            eprintln!("Error writing movie: {}", e);
+        }
+    }
+
+    // Set the joypad for the frame that's starting. Once the input runs out the window takes over.
+    fn latch_frame_input(&mut self) {
+        match self.frame_input.as_mut().map(FrameInput::next_frame) {
+            Some(Some(state)) => self.mem.lock().unwrap().set_buttons(state),
+            Some(None) => self.frame_input = None,
+            None => (),
+        }
+    }
+
+    // Handle window events, passing joypad presses on to memory, or to the frame input if there is one.
+    fn poll_window(&mut self) {
+        let buttons = match &mut self.lcd {
+            Some(lcd) => lcd.get_events(),
+            None => return,
+        };
+        if let Some(input) = &mut self.frame_input {
+            input.window_buttons(&buttons);
+        } else if !buttons.is_empty() {
+            let mut mem = self.mem.lock().unwrap();
+            for (button, pressed) in buttons {
+                mem.set_button(button, pressed);
+            }
+        }
+    }
+
     fn check_events(&mut self) {
         // Do nothing if we've terminated the application.
         if !self.is_alive() {
@@ -354,25 +464,30 @@ impl PPU {
 
         // Check window for termination events
         if self.cfg.state == PPUState::VBlank {
-            self.lcd.get_events();
+            self.poll_window();
         }
-        if !self.lcd.is_open() {
-            self.terminate();
-            return;
+        if let Some(lcd) = &self.lcd {
+            if !lcd.is_open() {
+                self.terminate();
+                return;
+            }
         }
 
-        // Check for LY==LYC
-        // TODO process the LYC interrupt here?
+        // Check for LY==LYC, the interrupt is handled by update_stat_interrupt().
         self.cfg.ly_eq_lyc = self.cfg.ly == self.cfg.lyc;
     }
 
-    // Check for register changes, and apply the corresponding settings differences.
+    // Check for register changes, and apply the corresponding settings differences. Returns whether
+    // the registers were read, which is only needed after the CPU wrote one of them.
     // TODO: Some registers can't be changed halfway through a scanline, check for those here.
-    fn pull_registers(&mut self) {
-        // Collect the values before writing to prevent borrowing issues.
-        // let regs = self.cfg.regs.cloned();
+    // This runs every cycle, so memory is locked once for the whole set of registers.
+    fn pull_registers(&mut self) -> bool {
+        let mut mref = self.mem.lock().unwrap();
+        if !mref.take_ppu_regs_written() {
+            return false;
+        }
         for reg in self.cfg.regs.iter() {
-            let val = self.mem_get(*reg as u16);
+            let val = (*mref).get(*reg as u16, MemClient::PPU);
 
             match reg {
                 PPUReg::Lcdc => {
@@ -406,49 +521,59 @@ impl PPU {
                 PPUReg::Vbk  => self.cfg.vbk_enable = val == 1,
             }
         }
+        true
     }
 
-    // Flush register changes to memory
-    fn push_registers(&mut self) {
-        let regs: Vec<PPUReg> = self.cfg.regs.iter().cloned().collect();
-        for reg in regs {
-            // Encode our current config state into actual register values
-            let val = match reg {
-                PPUReg::Lcdc => {
-                    (if self.cfg.lcd_enabled        { 1 } else { 0 } << 7) |
-                    (if self.cfg.win_map_high_bank  { 1 } else { 0 } << 6) |
-                    (if self.cfg.win_en             { 1 } else { 0 } << 5) |
-                    (if self.cfg.bg_data_low_bank   { 1 } else { 0 } << 4) |
-                    (if self.cfg.bg_map_high_bank   { 1 } else { 0 } << 3) |
-                    (if self.cfg.tall_objs          { 1 } else { 0 } << 2) |
-                    (if self.cfg.obj_en             { 1 } else { 0 } << 1) |
-                    (if self.cfg.bg_priority        { 1 } else { 0 } << 0)
-                },
-                PPUReg::Stat => {
-                    (0x1 << 7) | // Bit 7 of STAT always returns 1
-                    (if self.cfg.ly_eq_lyc_intr     { 1 } else { 0 } << 6) |
-                    (if self.cfg.oam_intr           { 1 } else { 0 } << 5) |
-                    (if self.cfg.vblank_intr        { 1 } else { 0 } << 4) |
-                    (if self.cfg.hblank_intr        { 1 } else { 0 } << 3) |
-                    (if self.cfg.ly_eq_lyc          { 1 } else { 0 } << 2) |
-                    (if self.cfg.lcd_enabled { (self.cfg.state as u8) & 0x3 } else { 0 })
-                },
-                PPUReg::Bgp => {
-                    self.cfg.bgp //TODO: split this up
-                },
-                PPUReg::Scy  => self.cfg.scy,
-                PPUReg::Scx  => self.cfg.scx,
-                PPUReg::Ly   => self.cfg.ly,
-                PPUReg::Lyc  => self.cfg.lyc,
-                PPUReg::Dma  => self.cfg.dma,
-                PPUReg::Obp0 => self.cfg.obp0,
-                PPUReg::Obp1 => self.cfg.obp1,
-                PPUReg::Wy   => self.cfg.wy,
-                PPUReg::Wx   => self.cfg.wx,
-                PPUReg::Vbk  => if self.cfg.vbk_enable { 1 } else { 0 },
-            };
+    // Flush register changes to memory, under a single lock like pull_registers. Only LY and STAT
+    // change by themselves, so the rest only need writing back after they were pulled.
+    fn push_registers(&mut self, pulled: bool) {
+        let status = (self.cfg.ly, self.encode_register(PPUReg::Stat));
+        if !pulled && status == self.pushed_status {
+            return;
+        }
+        self.pushed_status = status;
+
+        let mut mref = self.mem.lock().unwrap();
+        for reg in self.cfg.regs.iter() {
+            (*mref).set(self.encode_register(*reg), *reg as u16, MemClient::PPU);
+        }
+    }
 
-            self.mem_set(reg as u16, val);
+    // Encode our current config state into the actual register value
+    fn encode_register(&self, reg: PPUReg) -> u8 {
+        match reg {
+            PPUReg::Lcdc => {
+                (if self.cfg.lcd_enabled        { 1 } else { 0 } << 7) |
+                (if self.cfg.win_map_high_bank  { 1 } else { 0 } << 6) |
+                (if self.cfg.win_en             { 1 } else { 0 } << 5) |
+                (if self.cfg.bg_data_low_bank   { 1 } else { 0 } << 4) |
+                (if self.cfg.bg_map_high_bank   { 1 } else { 0 } << 3) |
+                (if self.cfg.tall_objs          { 1 } else { 0 } << 2) |
+                (if self.cfg.obj_en             { 1 } else { 0 } << 1) |
+                (if self.cfg.bg_priority        { 1 } else { 0 } << 0)
+            },
+            PPUReg::Stat => {
+                (0x1 << 7) | // Bit 7 of STAT always returns 1
+                (if self.cfg.ly_eq_lyc_intr     { 1 } else { 0 } << 6) |
+                (if self.cfg.oam_intr           { 1 } else { 0 } << 5) |
+                (if self.cfg.vblank_intr        { 1 } else { 0 } << 4) |
+                (if self.cfg.hblank_intr        { 1 } else { 0 } << 3) |
+                (if self.cfg.ly_eq_lyc          { 1 } else { 0 } << 2) |
+                (if self.cfg.lcd_enabled { (self.cfg.state as u8) & 0x3 } else { 0 })
+            },
+            PPUReg::Bgp => {
+                self.cfg.bgp //TODO: split this up
+            },
+            PPUReg::Scy  => self.cfg.scy,
+            PPUReg::Scx  => self.cfg.scx,
+            PPUReg::Ly   => self.cfg.ly,
+            PPUReg::Lyc  => self.cfg.lyc,
+            PPUReg::Dma  => self.cfg.dma,
+            PPUReg::Obp0 => self.cfg.obp0,
+            PPUReg::Obp1 => self.cfg.obp1,
+            PPUReg::Wy   => self.cfg.wy,
+            PPUReg::Wx   => self.cfg.wx,
+            PPUReg::Vbk  => if self.cfg.vbk_enable { 1 } else { 0 },
         }
     }
 
@@ -460,8 +585,8 @@ impl PPU {
         (*mref).get(addr, MemClient::PPU)
     }
 
-    fn mem_set(&mut self, addr: u16, val: u8) {
+    fn request_interrupt(&mut self, ir: Interrupt) {
         let mut mref = self.mem.lock().unwrap();
-        (*mref).set(val, addr, MemClient::PPU)
+        (*mref).request_interrupt(ir);
     }
 }
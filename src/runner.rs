@@ -0,0 +1,264 @@
+// Headless test ROM runners. These run a ROM without a window until it reports a result, so test
+// suites can be run from scripts and CI.
+
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+use crate::cpu::CPU;
+use crate::memory::Memory;
+use crate::movie::FrameInput;
+use crate::ppu::PPU;
+use crate::registers::*;
+use crate::trace::TraceLog;
+use crate::RuntimeConfig;
+
+// The Game Boy system clock runs at 4 MiHz.
+pub const CLOCKS_PER_SECOND: u64 = 4_194_304;
+
+// Mooneye test ROMs load the Fibonacci numbers into B, C, D, E, H and L to signal a pass.
+const MOONEYE_PASS: [u8; 6] = [3, 5, 8, 13, 21, 34];
+
+// How long to keep running after the result is printed, so details like failed test numbers that
+// follow it still make it into the output.
+const SERIAL_FLUSH_CLOCKS: u64 = CLOCKS_PER_SECOND / 2;
+
+#[derive(Clone, PartialEq)]
+pub enum TestResult {
+    Passed,
+    Failed,
+    Timeout,
+    Stopped(String)
+}
+
+impl TestResult {
+    // Process exit status for this result.
+    pub fn exit_code(&self) -> i32 {
+        match *self {
+            TestResult::Passed => 0,
+            _ => 1,
+        }
+    }
+}
+
+impl fmt::Display for TestResult {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TestResult::Passed => write!(f, "Passed"),
+            TestResult::Failed => write!(f, "Failed"),
+            TestResult::Timeout => write!(f, "Timed out"),
+            TestResult::Stopped(why) => write!(f, "Stopped: {}", why),
+        }
+    }
+}
+
+// Run a test ROM that prints its results through the serial port, like Blargg's test ROMs. The
+// output is echoed as it arrives, and the test finishes once it contains "Passed" or "Failed".
+// time_limit is in seconds of emulated time.
+pub fn run_serial_test(cpu: &mut CPU, running: &AtomicBool, time_limit: u64) -> TestResult {
+    let mut clock_limit = time_limit * CLOCKS_PER_SECOND;
+    let mut output = String::new();
+    let mut result = TestResult::Timeout;
+
+    loop {
+        if let Err(stopped) = step(cpu, running) {
+            return stopped;
+        }
+        if cpu.clocks() >= clock_limit {
+            if !output.ends_with('\n') { println!(); }
+            return result;
+        }
+
+        let sent = cpu.mem.lock().unwrap().take_serial_output();
+        if sent.is_empty() { continue; }
+
+        let text = String::from_utf8_lossy(&sent);
+        print!("{}", text);
+        output.push_str(&text);
+        if result != TestResult::Timeout { continue; }
+
+        if output.contains("Passed") {
+            result = TestResult::Passed;
+        } else if output.contains("Failed") {
+            result = TestResult::Failed;
+        }
+        if result != TestResult::Timeout {
+            clock_limit = cpu.clocks() + SERIAL_FLUSH_CLOCKS;
+        }
+    }
+}
+
+// Run a Mooneye test ROM, which executes LD B,B once it's finished and leaves the result in the
+// registers. time_limit is in seconds of emulated time.
+pub fn run_mooneye_test(cpu: &mut CPU, running: &AtomicBool, time_limit: u64) -> TestResult {
+    let clock_limit = time_limit * CLOCKS_PER_SECOND;
+
+    loop {
+        if let Err(stopped) = step(cpu, running) {
+            return stopped;
+        }
+        if cpu.take_magic_break() {
+            return if mooneye_regs(cpu) == MOONEYE_PASS { TestResult::Passed } else { TestResult::Failed };
+        }
+        if cpu.clocks() >= clock_limit {
+            return TestResult::Timeout;
+        }
+    }
+}
+
+// Run every Mooneye test ROM at the given path, which can be a single ROM or a directory that's
+// searched recursively. Prints a line per ROM and a summary, and returns the process exit status.
+pub fn run_mooneye_suite(path: &str, cfg: &RuntimeConfig, running: &AtomicBool) -> i32 {
+    let roms = match find_roms(Path::new(path)) {
+        Ok(roms) => roms,
+        Err(e) => {
+            eprintln!("Error reading \"{}\": {}", path, e);
+            return 1;
+        }
+    };
+
+    let mut passed = 0;
+    for rom in roms.iter() {
+        let mut cpu = match fs::read(rom) {
+            Ok(bytes) => headless_cpu(bytes, cfg),
+            Err(e) => {
+                println!("FAIL  {} (error reading the ROM: {})", rom.display(), e);
+                continue;
+            }
+        };
+        if let Some(trace_file) = &cfg.trace_file {
+            let trace_path = suite_trace_path(trace_file, Path::new(path), rom);
+            match create_trace(&trace_path, cfg) {
+                Ok(trace) => cpu.set_trace(trace),
+                Err(e) => {
+                    eprintln!("Error creating trace file {}: {}", trace_path.display(), e);
+                    return 1;
+                }
+            }
+        }
+        let result = run_mooneye_test(&mut cpu, running, cfg.time_limit);
+        cpu.flush_trace();
+        if result == TestResult::Passed {
+            passed += 1;
+            println!("PASS  {}", rom.display());
+        } else {
+            let regs = mooneye_regs(&cpu);
+            println!("FAIL  {} ({}, B:{:02x} C:{:02x} D:{:02x} E:{:02x} H:{:02x} L:{:02x})", rom.display(),
+                     result, regs[0], regs[1], regs[2], regs[3], regs[4], regs[5]);
+        }
+
+        if !running.load(Ordering::SeqCst) { break; }
+    }
+
+    println!("{} of {} test ROMs passed", passed, roms.len());
+    if passed == roms.len() { 0 } else { 1 }
+}
+
+// Run a ROM headless as fast as possible for time_limit seconds of emulated time, then report how
+// long that took and how it compares to a real Game Boy. Returns the process exit status.
+pub fn run_benchmark(cpu: &mut CPU, running: &AtomicBool, time_limit: u64) -> i32 {
+    let clock_limit = time_limit * CLOCKS_PER_SECOND;
+    let start = Instant::now();
+    let mut exit_code = 0;
+
+    while cpu.clocks() < clock_limit {
+        if let Err(stopped) = step(cpu, running) {
+            println!("{}", stopped);
+            exit_code = 1;
+            break;
+        }
+    }
+
+    let elapsed = start.elapsed().as_secs_f64();
+    let emulated = cpu.clocks() as f64 / CLOCKS_PER_SECOND as f64;
+    println!("Emulated {:.2}s in {:.2}s, {:.2}x real time ({:.2} MHz)", emulated, elapsed,
+             emulated / elapsed, cpu.clocks() as f64 / elapsed / 1e6);
+    exit_code
+}
+
+// Run a ROM headless until it ends or Ctrl+C is pressed, for when the debugger is doing the work.
+// Returns the process exit status, which is 1 since nothing here can pass.
+pub fn run_until_stopped(rom: Vec<u8>, cfg: &RuntimeConfig, frame_input: Option<FrameInput>,
+                         running: &AtomicBool) -> i32 {
+    let mut cpu = headless_cpu(rom, cfg);
+    if let Some(input) = frame_input {
+        cpu.ppu.set_frame_input(input);
+    }
+    loop {
+        if let Err(stopped) = step(&mut cpu, running) {
+            println!("{}", stopped);
+            return 1;
+        }
+    }
+}
+
+// Where a ROM's trace goes in a suite run. Tracing a single ROM writes to the -T file itself, and
+// tracing a directory makes -T a directory with a trace for each ROM, at the same relative path.
+fn suite_trace_path(trace_file: &str, suite: &Path, rom: &Path) -> PathBuf {
+    if !suite.is_dir() {
+        return PathBuf::from(trace_file);
+    }
+    Path::new(trace_file).join(rom.strip_prefix(suite).unwrap_or(rom)).with_extension("log")
+}
+
+fn create_trace(path: &Path, cfg: &RuntimeConfig) -> io::Result<TraceLog> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    TraceLog::create(&path.to_string_lossy(), cfg.trace_ly, cfg.trace_clocks)
+}
+
+// Run one instruction, or return why the test run can't continue.
+fn step(cpu: &mut CPU, running: &AtomicBool) -> Result<(), TestResult> {
+    if !running.load(Ordering::SeqCst) {
+        return Err(TestResult::Stopped("received Ctrl+C".to_string()));
+    }
+    if !cpu.tick() {
+        return Err(TestResult::Stopped("emulation ended".to_string()));
+    }
+    if cpu.is_stopped() && !cpu.ppu.is_playing_input() {
+        return Err(TestResult::Stopped("STOP with no input to wake it".to_string()));
+    }
+    if cpu.is_locked() {
+        return Err(TestResult::Stopped("the CPU locked up".to_string()));
+    }
+
+    Ok(())
+}
+
+fn mooneye_regs(cpu: &CPU) -> [u8; 6] {
+    [Reg8::B, Reg8::C, Reg8::D, Reg8::E, Reg8::H, Reg8::L].map(|r| cpu.regs.get(r))
+}
+
+// Build a freshly powered on system for the given ROM, with no window.
+fn headless_cpu(rom: Vec<u8>, cfg: &RuntimeConfig) -> CPU {
+    let mut mem = Memory::new(0x10000);
+    mem.load_rom(rom);
+    let mem = Arc::new(Mutex::new(mem));
+    let ppu = PPU::headless(mem.clone());
+    CPU::new(mem, ppu, cfg)
+}
+
+// Collect the .gb and .gbc files at path, in a stable order.
+fn find_roms(path: &Path) -> io::Result<Vec<PathBuf>> {
+    if !path.is_dir() {
+        return Ok(vec![path.to_path_buf()]);
+    }
+
+    let mut roms = Vec::new();
+    let mut entries: Vec<PathBuf> = fs::read_dir(path)?.map(|e| e.map(|e| e.path())).collect::<io::Result<_>>()?;
+    entries.sort();
+    for entry in entries {
+        if entry.is_dir() {
+            roms.extend(find_roms(&entry)?);
+        } else if entry.extension().is_some_and(|ext| ext == "gb" || ext == "gbc") {
+            roms.push(entry);
+        }
+    }
+
+    Ok(roms)
+}
@@ -0,0 +1,92 @@
+// Scripted input, for getting headless runs to the screen under test without anyone at the joypad.
+// A script is a list of presses, each a button, the frame it's pressed on and optionally for how
+// many frames, like "start@120,a@180:10". Script files use the same syntax, with presses separated
+// by commas or newlines and # starting a comment.
+//
+// Frames are counted from 0 at the first VBlank, the same way movies count them.
+
+use std::fs;
+
+use crate::joypad::Button;
+
+// Long enough for games that only read the joypad every few frames to notice.
+const DEFAULT_FRAMES: u64 = 5;
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+struct Press {
+    button: Button,
+    frame:  u64,
+    frames: u64,
+}
+
+fn parse_press(item: &str) -> Result<Press, String> {
+    let (name, when) = item.split_once('@').ok_or_else(|| format!("\"{}\" should be button@frame or button@frame:frames", item))?;
+    let button = Button::from_name(name.trim())
+        .ok_or_else(|| format!("\"{}\" isn't a button, expected one of {}", name.trim(), Button::NAMES.join(", ")))?;
+    let (frame, frames) = when.split_once(':').unwrap_or((when, ""));
+    let frame = frame.trim().parse().map_err(|_| format!("bad frame number \"{}\" in \"{}\"", frame.trim(), item))?;
+    let frames = match frames.trim() {
+        "" => DEFAULT_FRAMES,
+        n => match n.parse() {
+            Ok(0) | Err(_) => return Err(format!("bad frame count \"{}\" in \"{}\"", n, item)),
+            Ok(n) => n,
+        },
+    };
+    Ok(Press { button, frame, frames })
+}
+
+#[derive(Debug)]
+pub struct Script {
+    presses: Vec<Press>,
+    frame:   u64,
+}
+
+impl Script {
+    // Parse the presses in a --input argument.
+    pub fn parse(text: &str) -> Result<Script, String> {
+        let presses = text.split(',').map(str::trim).filter(|item| !item.is_empty())
+            .map(parse_press).collect::<Result<_, _>>()?;
+        Ok(Script { presses, frame: 0 })
+    }
+
+    // Read a script file. Errors include the line they were found on.
+    pub fn load(path: &str) -> Result<Script, String> {
+        let text = fs::read_to_string(path).map_err(|e| format!("{}: {}", path, e))?;
+        let mut presses = Vec::new();
+        for (i, line) in text.lines().enumerate() {
+            let line = line.split('#').next().unwrap_or("");
+            let script = Script::parse(line).map_err(|e| format!("{}:{}: {}", path, i + 1, e))?;
+            presses.extend(script.presses);
+        }
+        Ok(Script { presses, frame: 0 })
+    }
+
+    // The buttons held in the frame that's starting, or None once the last press has been let go.
+    pub fn next_frame(&mut self) -> Option<u8> {
+        let end = self.presses.iter().map(|p| p.frame + p.frames).max().unwrap_or(0);
+        if self.frame > end {
+            return None;
+        }
+        let frame = self.frame;
+        self.frame += 1;
+        Some(self.presses.iter().filter(|p| (p.frame..p.frame + p.frames).contains(&frame))
+            .fold(0, |state, p| state | p.button.mask()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn presses_buttons_for_their_frames() {
+        let mut script = Script::parse("start@1, a@2:2,B@2:1").unwrap();
+        let frames: Vec<Option<u8>> = (0..9).map(|_| script.next_frame()).collect();
+        let (start, a, b) = (Button::Start.mask(), Button::A.mask(), Button::B.mask());
+        assert_eq!(frames, [Some(0), Some(start), Some(start | a | b), Some(start | a), Some(start), Some(start), Some(0), None, None]);
+
+        assert_eq!(Script::parse("turbo@5").unwrap_err(), "\"turbo\" isn't a button, expected one of right, left, up, down, a, b, select, start");
+        assert_eq!(Script::parse("a@1:0").unwrap_err(), "bad frame count \"0\" in \"a@1:0\"");
+        assert_eq!(Script::parse("a").unwrap_err(), "\"a\" should be button@frame or button@frame:frames");
+    }
+}
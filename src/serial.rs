@@ -0,0 +1,46 @@
+// The serial port. There's never a link cable partner, so a transfer started with the internal clock
+// shifts out SB, shifts in all ones, and then raises the serial interrupt. Every byte sent is kept,
+// since test ROMs print their results through the serial port.
+
+pub const SB_ADDR: u16 = 0xFF01; // SB - Serial transfer data
+pub const SC_ADDR: u16 = 0xFF02; // SC - Serial transfer control, bit 7 starts a transfer, bit 0 selects the internal clock
+
+// The internal clock runs at 8192 Hz, so each bit takes 128 machine cycles.
+const TRANSFER_MCYCLES: u32 = 8 * 128;
+
+pub struct Serial {
+    output:    Vec<u8>, // Bytes sent since the output was last taken.
+    countdown: u32      // Machine cycles left in the current transfer, or 0 if there isn't one.
+}
+
+impl Serial {
+    pub fn new() -> Serial {
+        Serial {
+            output:    Vec::new(),
+            countdown: 0
+        }
+    }
+
+    // Handle a write to SC. Transfers clocked externally never finish, since nothing drives the clock.
+    pub fn write_control(&mut self, sc: u8, sb: u8) {
+        if (sc & 0x81) == 0x81 {
+            self.output.push(sb);
+            self.countdown = TRANSFER_MCYCLES;
+        }
+    }
+
+    // Advance the transfer by one machine cycle, and return true when it completes.
+    pub fn tick(&mut self) -> bool {
+        if self.countdown == 0 {
+            return false;
+        }
+
+        self.countdown -= 1;
+        self.countdown == 0
+    }
+
+    // Take the bytes sent since the last call.
+    pub fn take_output(&mut self) -> Vec<u8> {
+        std::mem::take(&mut self.output)
+    }
+}
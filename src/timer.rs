@@ -0,0 +1,146 @@
+// The timer. DIV is the top byte of a 16-bit counter that counts every clock, and TIMA counts up
+// whenever the counter bit selected by TAC falls from 1 to 0 while the timer is enabled. Because it's
+// edge triggered, resetting DIV or changing TAC while the selected bit is 1 also bumps TIMA.
+//
+// When TIMA overflows it reads as 0 for one machine cycle, and is then reloaded from TMA along with
+// the timer interrupt being requested. Writing TIMA during that first cycle cancels the reload, and
+// during the reload cycle TIMA writes are ignored while TMA writes go straight through to TIMA.
+
+pub const DIV_ADDR: u16  = 0xFF04; // DIV - Divider, the upper byte of the system counter
+pub const TIMA_ADDR: u16 = 0xFF05; // TIMA - Timer counter
+pub const TMA_ADDR: u16  = 0xFF06; // TMA - Timer modulo, reloaded into TIMA when it overflows
+pub const TAC_ADDR: u16  = 0xFF07; // TAC - Timer control, bit 2 enables the timer and bits 0-1 pick the rate
+
+// The counter bit TIMA follows for each TAC rate: 4096 Hz, 262144 Hz, 65536 Hz and 16384 Hz.
+const RATE_BITS: [u16; 4] = [9, 3, 5, 7];
+
+#[derive(Copy, Clone, PartialEq, Eq)]
+enum Reload {
+    Idle,
+    Overflowed, // TIMA overflowed this cycle, and reads as 0 until the next.
+    Reloading,  // TIMA was just reloaded from TMA.
+}
+
+pub struct Timer {
+    counter: u16,
+    tima:    u8,
+    tma:     u8,
+    tac:     u8,
+    reload:  Reload
+}
+
+impl Timer {
+    pub fn new() -> Timer {
+        Timer {
+            counter: 0,
+            tima:    0,
+            tma:     0,
+            tac:     0,
+            reload:  Reload::Idle
+        }
+    }
+
+    pub fn read(&self, addr: u16) -> u8 {
+        match addr {
+            DIV_ADDR  => (self.counter >> 8) as u8,
+            TIMA_ADDR => self.tima,
+            TMA_ADDR  => self.tma,
+            _         => self.tac | 0xF8,
+        }
+    }
+
+    pub fn write(&mut self, addr: u16, val: u8) {
+        let was_high = self.selected_bit();
+        match addr {
+            DIV_ADDR => self.counter = 0,
+            TIMA_ADDR => match self.reload {
+                Reload::Reloading => (),
+                Reload::Overflowed => { self.tima = val; self.reload = Reload::Idle; },
+                Reload::Idle => self.tima = val,
+            },
+            TMA_ADDR => {
+                self.tma = val;
+                if self.reload == Reload::Reloading { self.tima = val; }
+            },
+            _ => self.tac = val & 0x07,
+        }
+        if was_high && !self.selected_bit() { self.increment(); }
+    }
+
+    // Set DIV without the side effects of a write, for the state the boot ROM leaves behind.
+    pub fn set_divider(&mut self, div: u8) {
+        self.counter = (div as u16) << 8;
+    }
+
+    // Advance the timer by one machine cycle, and return true when the timer interrupt is requested.
+    pub fn tick(&mut self) -> bool {
+        let mut interrupt = false;
+        match self.reload {
+            Reload::Overflowed => {
+                self.tima = self.tma;
+                self.reload = Reload::Reloading;
+                interrupt = true;
+            },
+            Reload::Reloading => self.reload = Reload::Idle,
+            Reload::Idle => (),
+        }
+
+        let was_high = self.selected_bit();
+        self.counter = self.counter.wrapping_add(4);
+        if was_high && !self.selected_bit() { self.increment(); }
+        interrupt
+    }
+
+    // The input to TIMA's edge detector: the selected counter bit, gated by the enable bit.
+    fn selected_bit(&self) -> bool {
+        (self.tac & 0x04) != 0 && (self.counter >> RATE_BITS[(self.tac & 0x03) as usize]) & 1 != 0
+    }
+
+    fn increment(&mut self) {
+        let (tima, overflowed) = self.tima.overflowing_add(1);
+        self.tima = tima;
+        if overflowed { self.reload = Reload::Overflowed; }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn overflow_reloads_from_tma_a_cycle_later() {
+        let mut timer = Timer::new();
+        timer.write(TAC_ADDR, 0x05); // Enabled, TIMA counting every 4 machine cycles.
+        timer.write(TMA_ADDR, 0x42);
+        timer.write(TIMA_ADDR, 0xFF);
+
+        let interrupts: Vec<bool> = (0..4).map(|_| timer.tick()).collect();
+        assert_eq!(interrupts, [false, false, false, false]);
+        assert_eq!(timer.read(TIMA_ADDR), 0x00);
+        assert!(timer.tick());
+        assert_eq!(timer.read(TIMA_ADDR), 0x42);
+
+        // TIMA writes are ignored in the reload cycle, and cancel the reload in the cycle before it.
+        timer.write(TIMA_ADDR, 0x00);
+        assert_eq!(timer.read(TIMA_ADDR), 0x42);
+        timer.tick();
+        timer.write(TIMA_ADDR, 0xFF);
+        while timer.read(TIMA_ADDR) == 0xFF { timer.tick(); }
+        timer.write(TIMA_ADDR, 0x10);
+        assert!(!timer.tick());
+        assert_eq!(timer.read(TIMA_ADDR), 0x10);
+    }
+
+    #[test]
+    fn resetting_div_with_the_selected_bit_set_bumps_tima() {
+        let mut timer = Timer::new();
+        timer.write(TAC_ADDR, 0x05);
+        timer.tick();
+        timer.tick();
+        assert_eq!(timer.read(TIMA_ADDR), 0x00);
+        timer.write(DIV_ADDR, 0x12);
+        assert_eq!(timer.read(TIMA_ADDR), 0x01);
+        assert_eq!(timer.read(DIV_ADDR), 0x00);
+        assert_eq!(timer.read(TAC_ADDR), 0xFD);
+    }
+}
@@ -0,0 +1,139 @@
+// Execution traces in the gameboy-doctor format, for comparing runs against other emulators. Each
+// instruction gets one line with the registers before it runs and the four bytes starting at PC:
+//
+//   A:01 F:B0 B:00 C:13 D:00 E:D8 H:01 L:4D SP:FFFE PC:0100 PCMEM:00,C3,13,02
+//
+// LY and the number of system clocks run so far can be added as extra columns. The clocks count at
+// the same rate in double speed mode. Reference traces usually don't have these columns, so
+// they're off by default.
+
+use std::fmt::{self, Display, Formatter};
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::convert::TryInto;
+use std::str::FromStr;
+
+pub mod diff;
+
+// The state of the system at the start of one instruction.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct TraceState {
+    pub a: u8,
+    pub f: u8,
+    pub b: u8,
+    pub c: u8,
+    pub d: u8,
+    pub e: u8,
+    pub h: u8,
+    pub l: u8,
+    pub sp: u16,
+    pub pc: u16,
+    pub pcmem: [u8; 4],
+    pub ly: Option<u8>,
+    pub clocks: Option<u64>,
+}
+
+impl Display for TraceState {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "A:{:02X} F:{:02X} B:{:02X} C:{:02X} D:{:02X} E:{:02X} H:{:02X} L:{:02X} SP:{:04X} PC:{:04X} PCMEM:{:02X},{:02X},{:02X},{:02X}",
+               self.a, self.f, self.b, self.c, self.d, self.e, self.h, self.l, self.sp, self.pc,
+               self.pcmem[0], self.pcmem[1], self.pcmem[2], self.pcmem[3])?;
+        if let Some(ly) = self.ly { write!(f, " LY:{:02X}", ly)?; }
+        if let Some(clocks) = self.clocks { write!(f, " CY:{}", clocks)?; }
+        Ok(())
+    }
+}
+
+impl FromStr for TraceState {
+    type Err = String;
+
+    // Parse a line in the format above. The LY and CY columns are optional, and any other columns
+    // are ignored, so traces with extra information from other emulators still work.
+    fn from_str(line: &str) -> Result<TraceState, String> {
+        let mut fields = std::collections::HashMap::new();
+        for field in line.split_whitespace() {
+            let (name, value) = field.split_once(':').ok_or_else(|| format!("\"{}\" isn't a NAME:VALUE column", field))?;
+            fields.insert(name, value);
+        }
+
+        let hex = |name: &str| -> Result<u16, String> {
+            let value = fields.get(name).ok_or_else(|| format!("missing the {} column", name))?;
+            u16::from_str_radix(value, 16).map_err(|_| format!("bad {} value \"{}\"", name, value))
+        };
+        let reg = |name: &str| hex(name).map(|val| val as u8);
+
+        let pcmem_str = fields.get("PCMEM").ok_or("missing the PCMEM column")?;
+        let pcmem: Vec<u8> = pcmem_str.split(',').map(|b| u8::from_str_radix(b, 16)).collect::<Result<_, _>>()
+            .map_err(|_| format!("bad PCMEM value \"{}\"", pcmem_str))?;
+        let pcmem: [u8; 4] = pcmem.try_into().map_err(|_| format!("PCMEM should have 4 bytes, not \"{}\"", pcmem_str))?;
+
+        let ly = match fields.get("LY") {
+            Some(_) => Some(reg("LY")?),
+            None => None,
+        };
+        let clocks = match fields.get("CY") {
+            Some(value) => Some(value.parse().map_err(|_| format!("bad CY value \"{}\"", value))?),
+            None => None,
+        };
+
+        Ok(TraceState {
+            a: reg("A")?, f: reg("F")?, b: reg("B")?, c: reg("C")?, d: reg("D")?, e: reg("E")?, h: reg("H")?, l: reg("L")?,
+            sp: hex("SP")?, pc: hex("PC")?, pcmem, ly, clocks,
+        })
+    }
+}
+
+// Read a whole trace file. Errors include the line they were found on.
+pub fn read_trace(path: &str) -> Result<Vec<TraceState>, String> {
+    let file = File::open(path).map_err(|e| format!("{}: {}", path, e))?;
+    let mut states = Vec::new();
+    for (i, line) in BufReader::new(file).lines().enumerate() {
+        let line = line.map_err(|e| format!("{}: {}", path, e))?;
+        if line.trim().is_empty() { continue; }
+        states.push(line.parse().map_err(|e| format!("{}:{}: {}", path, i + 1, e))?);
+    }
+    Ok(states)
+}
+
+// A trace being written to a file, with the optional columns it was asked for.
+pub struct TraceLog {
+    out: BufWriter<File>,
+    ly: bool,
+    clocks: bool,
+}
+
+impl TraceLog {
+    pub fn create(path: &str, ly: bool, clocks: bool) -> io::Result<TraceLog> {
+        Ok(TraceLog { out: BufWriter::new(File::create(path)?), ly, clocks })
+    }
+
+    pub fn write(&mut self, mut state: TraceState) -> io::Result<()> {
+        if !self.ly { state.ly = None; }
+        if !self.clocks { state.clocks = None; }
+        writeln!(self.out, "{}", state)
+    }
+
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.out.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formats_lines_like_gameboy_doctor() {
+        let mut state = TraceState {
+            a: 0x01, f: 0xB0, b: 0x00, c: 0x13, d: 0x00, e: 0xD8, h: 0x01, l: 0x4D, sp: 0xFFFE, pc: 0x0100,
+            pcmem: [0x00, 0xC3, 0x13, 0x02], ly: None, clocks: None,
+        };
+        assert_eq!(state.to_string(), "A:01 F:B0 B:00 C:13 D:00 E:D8 H:01 L:4D SP:FFFE PC:0100 PCMEM:00,C3,13,02");
+
+        state.ly = Some(0x90);
+        state.clocks = Some(1234);
+        assert!(state.to_string().ends_with("PCMEM:00,C3,13,02 LY:90 CY:1234"));
+        assert_eq!(state.to_string().parse(), Ok(state));
+        assert_eq!("A:01 F:B0 B:00".parse::<TraceState>(), Err("missing the PCMEM column".to_string()));
+    }
+}
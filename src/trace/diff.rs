@@ -0,0 +1,103 @@
+// Comparing our traces against a reference emulator's, to find the first instruction where the two
+// disagree. The traces don't have to start at the same point, one of them might include the boot
+// ROM for example, so they're lined up on the first state they have in common first.
+
+use super::TraceState;
+use crate::disasm;
+
+// Where two traces stop agreeing, as indexes into each of them.
+#[derive(PartialEq, Eq, Debug)]
+pub enum Comparison {
+    Matching { ours_start: usize, ref_start: usize, lines: usize },
+    Diverged { ours_start: usize, ref_start: usize, ours: usize, reference: usize },
+}
+
+// The differences between two states, like "A: 01 vs 00". The clock count is only compared when
+// both traces have it, and the same for LY.
+pub fn differences(ours: &TraceState, reference: &TraceState) -> Vec<String> {
+    let mut diffs = Vec::new();
+    let regs = [("A", ours.a, reference.a), ("B", ours.b, reference.b), ("C", ours.c, reference.c),
+                ("D", ours.d, reference.d), ("E", ours.e, reference.e), ("H", ours.h, reference.h),
+                ("L", ours.l, reference.l)];
+    for (name, a, b) in regs.iter().filter(|(_, a, b)| a != b) {
+        diffs.push(format!("{}: {:02X} vs {:02X}", name, a, b));
+    }
+    if ours.f != reference.f {
+        diffs.push(format!("F: {:02X} ({}) vs {:02X} ({})", ours.f, flag_names(ours.f), reference.f, flag_names(reference.f)));
+    }
+    if ours.sp != reference.sp { diffs.push(format!("SP: {:04X} vs {:04X}", ours.sp, reference.sp)); }
+    if ours.pc != reference.pc { diffs.push(format!("PC: {:04X} vs {:04X}", ours.pc, reference.pc)); }
+    if ours.pcmem != reference.pcmem {
+        diffs.push(format!("PCMEM: {:02X?} vs {:02X?}", ours.pcmem, reference.pcmem));
+    }
+    if let (Some(a), Some(b)) = (ours.ly, reference.ly) {
+        if a != b { diffs.push(format!("LY: {:02X} vs {:02X}", a, b)); }
+    }
+    if let (Some(a), Some(b)) = (ours.clocks, reference.clocks) {
+        if a != b { diffs.push(format!("CY: {} vs {} ({:+})", a, b, a as i64 - b as i64)); }
+    }
+    diffs
+}
+
+fn flag_names(f: u8) -> String {
+    ["Z", "N", "H", "C"].iter().enumerate()
+        .map(|(i, name)| if f & (0x80 >> i) != 0 { *name } else { "-" })
+        .collect()
+}
+
+// Line the traces up on the first state they share, preferring whichever skips the fewest lines.
+// Traces that never line up are compared from their first lines.
+fn align(ours: &[TraceState], reference: &[TraceState]) -> (usize, usize) {
+    let same = |a: &TraceState, b: &TraceState| differences(a, b).is_empty();
+    let skip_ours = reference.first().and_then(|first| ours.iter().position(|s| same(s, first)));
+    let skip_ref = ours.first().and_then(|first| reference.iter().position(|s| same(first, s)));
+    match (skip_ours, skip_ref) {
+        (Some(o), Some(r)) if o <= r => (o, 0),
+        (_, Some(r)) => (0, r),
+        (Some(o), None) => (o, 0),
+        (None, None) => (0, 0),
+    }
+}
+
+pub fn compare(ours: &[TraceState], reference: &[TraceState]) -> Comparison {
+    let (ours_start, ref_start) = align(ours, reference);
+    let pairs = ours[ours_start..].iter().zip(reference[ref_start..].iter());
+    for (i, (a, b)) in pairs.enumerate() {
+        if !differences(a, b).is_empty() {
+            return Comparison::Diverged { ours_start, ref_start, ours: ours_start + i, reference: ref_start + i };
+        }
+    }
+
+    let lines = (ours.len() - ours_start).min(reference.len() - ref_start);
+    Comparison::Matching { ours_start, ref_start, lines }
+}
+
+// A trace line with the instruction it's about to run, for showing the lead up to a divergence.
+pub fn describe(state: &TraceState) -> String {
+    let (text, _) = disasm::disassemble(&state.pcmem, state.pc);
+    format!("{:04X}  {:<18} {}", state.pc, text, state)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn state(pc: u16, a: u8) -> TraceState {
+        TraceState { a, f: 0xB0, b: 0, c: 0x13, d: 0, e: 0xD8, h: 0x01, l: 0x4D, sp: 0xFFFE, pc,
+                     pcmem: [0; 4], ly: None, clocks: None }
+    }
+
+    #[test]
+    fn finds_the_first_divergence_after_aligning() {
+        let ours = vec![state(0x100, 1), state(0x101, 1), state(0x102, 2), state(0x103, 3)];
+        let reference = vec![state(0x0fe, 1), state(0x0ff, 1), state(0x100, 1), state(0x101, 1), state(0x102, 9)];
+
+        assert_eq!(compare(&ours, &reference), Comparison::Diverged { ours_start: 0, ref_start: 2, ours: 2, reference: 4 });
+        assert_eq!(differences(&ours[2], &reference[4]), vec!["A: 02 vs 09".to_string()]);
+        assert_eq!(compare(&ours[..2], &reference), Comparison::Matching { ours_start: 0, ref_start: 2, lines: 2 });
+
+        let mut flags = state(0x100, 1);
+        flags.f = 0x80;
+        assert_eq!(differences(&flags, &ours[0]), vec!["F: 80 (Z---) vs B0 (Z-HC)".to_string()]);
+    }
+}
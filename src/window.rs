@@ -2,10 +2,13 @@ use sdl2;
 use sdl2::video;
 use sdl2::render;
 use sdl2::event::Event;
-use sdl2::keyboard::Keycode;
 use sdl2::pixels::Color;
 use sdl2::pixels::PixelFormatEnum;
 
+use crate::config::{Bindings, Hotkey};
+use crate::input::Input;
+use crate::joypad::Button;
+
 pub struct Window {
     sdl: sdl2::Sdl,
     canvas: render::Canvas<video::Window>,
@@ -13,10 +16,12 @@ pub struct Window {
     height: u32,
     event_cnt: u32,
     open: bool,
+    debug_requested: bool,
+    input: Input,
 }
 
 impl Window {
-    pub fn new(w: usize, h: usize) -> Self {
+    pub fn new(w: usize, h: usize, bindings: Bindings) -> Self {
         let (wi, hi) = (w as u32, h as u32);
         let sdl = sdl2::init().unwrap();
         let video = sdl.video().unwrap();
@@ -25,6 +30,8 @@ impl Window {
                        .build()
                        .unwrap();
 
+        let controllers = sdl.game_controller().map_err(|e| eprintln!("Controllers aren't available: {}", e)).ok();
+
         let mut can = win.into_canvas().build().unwrap();
         can.set_draw_color(Color::RGB(0, 255, 255));
 
@@ -35,6 +42,8 @@ impl Window {
             height: hi,
             event_cnt: 0,
             open: true,
+            debug_requested: false,
+            input: Input::new(bindings, controllers),
         }
     }
 
@@ -49,12 +58,14 @@ impl Window {
         self.canvas.present();
     }
 
+    // Handle window events, returning the buttons pressed or released since the last call.
     // TODO: Move this to another thread. Maybe the entire window could be run in a binary package
     // on a separate thread? It could set up channels to communicate with the PPU/CPU.
-    pub fn get_events(&mut self) {
+    pub fn get_events(&mut self) -> Vec<(Button, bool)> {
+        let mut buttons = Vec::new();
         self.event_cnt += 1;
         if self.event_cnt < 250 {
-            return;
+            return buttons;
         } else {
             self.event_cnt = 0;
         }
@@ -62,12 +73,23 @@ impl Window {
         let mut events = self.sdl.event_pump().unwrap();
         for event in events.poll_iter() {
             match event {
-                Event::Quit {..} | Event::KeyDown { keycode: Some(Keycode::Escape), .. } => {
-                    self.close();
-                },
-                _ => ()
+                Event::Quit {..} => self.close(),
+                _ => buttons.extend(self.input.handle_event(&event)),
             }
         }
+
+        for hotkey in self.input.take_hotkeys() {
+            match hotkey {
+                Hotkey::Quit => self.close(),
+                Hotkey::Debug => self.debug_requested = true,
+            }
+        }
+        buttons
+    }
+
+    // Whether the debug hotkey was pressed since the last call.
+    pub fn take_debug_request(&mut self) -> bool {
+        std::mem::take(&mut self.debug_requested)
     }
 
     pub fn is_open(&self) -> bool {